@@ -0,0 +1,320 @@
+//! Puzzle input downloads, cached so that each day is only fetched once.
+//!
+//! Settings come from a TOML config file, with these keys, each of which
+//! an environment variable overrides:
+//!
+//! ```text
+//! session   = "..."                        # AOC_SESSION
+//! base_url  = "https://adventofcode.com"   # AOC_BASE_URL
+//! year      = 2023                         # AOC_YEAR
+//! cache_dir = ".aoc-cache"                 # AOC_CACHE_DIR
+//! ```
+//!
+//! The file is `$AOC_CONFIG`, or `aoc/config.toml` under the user's
+//! config directory.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_YEAR: u32 = 2023;
+pub const DEFAULT_CACHE_DIR: &str = ".aoc-cache";
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// No session token in the environment nor in the config file.
+    MissingSession,
+    /// Puzzle days go from 1 to 25.
+    InvalidDay(u32),
+    Config { path: PathBuf, message: String },
+    /// The server answered with an error status.
+    Http { url: String, status: u16 },
+    /// The server couldn't be reached, or the connection failed.
+    Transport { url: String, message: String },
+    Io { path: PathBuf, error: io::Error },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchError::MissingSession =>
+                write!(f, "no session token, set AOC_SESSION or `session` in the config file"),
+            FetchError::InvalidDay(day) =>
+                write!(f, "day {} is not between 1 and 25", day),
+            FetchError::Config { path, message } =>
+                write!(f, "{}: {}", path.display(), message),
+            FetchError::Http { url, status } =>
+                write!(f, "{}: HTTP status {}", url, status),
+            FetchError::Transport { url, message } =>
+                write!(f, "{}: {}", url, message),
+            FetchError::Io { path, error } =>
+                write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+    pub year: u32,
+    pub cache_dir: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            year: DEFAULT_YEAR,
+            cache_dir: PathBuf::from(DEFAULT_CACHE_DIR),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config file, when there is one, then lets the variables
+    /// given by `env` override it.
+    pub fn load(env: impl Fn(&str) -> Option<String>) -> Result<Config, FetchError> {
+        let path = env("AOC_CONFIG").map(PathBuf::from).or_else(|| {
+            let dir = env("XDG_CONFIG_HOME").map(PathBuf::from)
+                .or_else(|| env("HOME").map(|h| Path::new(&h).join(".config")))?;
+            Some(dir.join("aoc").join("config.toml"))
+        });
+
+        let mut config = match path {
+            Some(path) if path.exists() => {
+                let contents = fs::read_to_string(&path)
+                    .map_err(|error| FetchError::Io { path: path.clone(), error })?;
+                Config::parse(&contents)
+                    .map_err(|message| FetchError::Config { path, message })?
+            },
+            _ => Config::default(),
+        };
+
+        if let Some(session) = env("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Some(base_url) = env("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+        if let Some(year) = env("AOC_YEAR") {
+            config.year = year.parse().map_err(|_| FetchError::Config {
+                path: "AOC_YEAR".into(),
+                message: format!("invalid year {:?}", year),
+            })?;
+        }
+        if let Some(cache_dir) = env("AOC_CACHE_DIR") {
+            config.cache_dir = PathBuf::from(cache_dir);
+        }
+        Ok(config)
+    }
+
+    fn parse(contents: &str) -> Result<Config, String> {
+        let table: toml::Table = contents.parse()
+            .map_err(|e: toml::de::Error| e.message().to_owned())?;
+        let mut config = Config::default();
+        for (key, value) in &table {
+            match (key.as_str(), value) {
+                ("session", toml::Value::String(s)) => config.session = Some(s.to_owned()),
+                ("base_url", toml::Value::String(s)) => config.base_url = s.to_owned(),
+                ("year", toml::Value::Integer(y)) => config.year = u32::try_from(*y)
+                    .map_err(|_| format!("invalid year {}", y))?,
+                ("cache_dir", toml::Value::String(s)) => config.cache_dir = PathBuf::from(s),
+                ("session" | "base_url" | "year" | "cache_dir", v) =>
+                    return Err(format!("unexpected {} for `{}`", v.type_str(), key)),
+                _ => return Err(format!("unknown key `{}`", key)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Where inputs are downloaded from, so that tests don't need a network.
+pub trait Backend {
+    fn get(&self, url: &str, session: &str) -> Result<String, FetchError>;
+}
+
+/// Plain HTTP(S) requests, authenticated with the session cookie.
+pub struct HttpBackend {
+    agent: ureq::Agent,
+}
+
+impl Default for HttpBackend {
+    fn default() -> Self {
+        HttpBackend { agent: ureq::AgentBuilder::new().build() }
+    }
+}
+
+impl Backend for HttpBackend {
+    fn get(&self, url: &str, session: &str) -> Result<String, FetchError> {
+        let response = self.agent.get(url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", concat!("aoc-fetch/", env!("CARGO_PKG_VERSION")))
+            .call();
+        match response {
+            Ok(r) => r.into_string()
+                .map_err(|e| FetchError::Transport { url: url.to_owned(), message: e.to_string() }),
+            Err(ureq::Error::Status(status, _)) =>
+                Err(FetchError::Http { url: url.to_owned(), status }),
+            Err(e) => Err(FetchError::Transport { url: url.to_owned(), message: e.to_string() }),
+        }
+    }
+}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> FetchError {
+    let path = path.to_owned();
+    |error| FetchError::Io { path, error }
+}
+
+pub struct Fetcher<B: Backend> {
+    config: Config,
+    backend: B,
+}
+
+impl<B: Backend> Fetcher<B> {
+    pub fn new(config: Config, backend: B) -> Self {
+        Fetcher { config, backend }
+    }
+
+    pub fn url(&self, day: u32) -> String {
+        let base_url = self.config.base_url.trim_end_matches('/');
+        format!("{}/{}/day/{}/input", base_url, self.config.year, day)
+    }
+
+    pub fn cache_path(&self, day: u32) -> PathBuf {
+        self.config.cache_dir
+            .join(self.config.year.to_string())
+            .join(format!("day-{:02}", day))
+    }
+
+    /// Path of the day's input, downloading it unless already cached.
+    pub fn fetch(&self, day: u32) -> Result<PathBuf, FetchError> {
+        if !(1..=25).contains(&day) {
+            return Err(FetchError::InvalidDay(day));
+        }
+
+        let path = self.cache_path(day);
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let session = self.config.session.as_deref().ok_or(FetchError::MissingSession)?;
+        let input = self.backend.get(&self.url(day), session)?;
+
+        // written aside then renamed, so that an interrupted download is
+        // never taken for a cached one
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir).map_err(io_error(dir))?;
+        let partial = path.with_extension("part");
+        fs::write(&partial, input).map_err(io_error(&partial))?;
+        fs::rename(&partial, &path).map_err(io_error(&path))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    /// Serves canned inputs, remembering the requests.
+    struct MockBackend {
+        inputs: HashMap<String, String>,
+        requests: RefCell<Vec<(String, String)>>,
+    }
+
+    impl Backend for &MockBackend {
+        fn get(&self, url: &str, session: &str) -> Result<String, FetchError> {
+            self.requests.borrow_mut().push((url.to_owned(), session.to_owned()));
+            match self.inputs.get(url) {
+                Some(input) => Ok(input.clone()),
+                None => Err(FetchError::Http { url: url.to_owned(), status: 404 }),
+            }
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        |name| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+    }
+
+    #[test]
+    fn fetches_once() {
+        let url = "http://stub/2023/day/5/input".to_owned();
+        let backend = MockBackend {
+            inputs: HashMap::from([(url.clone(), "seeds: 1 2\n".to_owned())]),
+            requests: RefCell::new(vec![]),
+        };
+        let config = Config {
+            session: Some("abc".to_owned()),
+            base_url: "http://stub/".to_owned(),
+            cache_dir: temp_dir("fetches-once"),
+            ..Config::default()
+        };
+        let fetcher = Fetcher::new(config, &backend);
+
+        let path = fetcher.fetch(5).unwrap();
+        assert!(path.ends_with("2023/day-05"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "seeds: 1 2\n");
+        assert_eq!(fetcher.fetch(5).unwrap(), path);
+        assert_eq!(*backend.requests.borrow(), [(url, "abc".to_owned())]);
+
+        assert!(matches!(fetcher.fetch(6), Err(FetchError::Http { status: 404, .. })));
+        assert!(!fetcher.cache_path(6).exists());
+        assert!(matches!(fetcher.fetch(26), Err(FetchError::InvalidDay(26))));
+    }
+
+    #[test]
+    fn cached_days_need_no_session() {
+        let backend = MockBackend { inputs: HashMap::new(), requests: RefCell::new(vec![]) };
+        let config = Config { cache_dir: temp_dir("no-session"), ..Config::default() };
+        let fetcher = Fetcher::new(config, &backend);
+
+        assert!(matches!(fetcher.fetch(1), Err(FetchError::MissingSession)));
+
+        let path = fetcher.cache_path(1);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "1abc2\n").unwrap();
+        assert_eq!(fetcher.fetch(1).unwrap(), path);
+        assert!(backend.requests.borrow().is_empty());
+    }
+
+    #[test]
+    fn config_file_and_environment() {
+        let dir = temp_dir("config");
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("config.toml");
+        fs::write(&file, "session = \"from-file\"\nyear = 2022\ncache_dir = \"inputs\"\n").unwrap();
+        let file = file.to_str().unwrap();
+
+        let config = Config::load(env(&[("AOC_CONFIG", file)])).unwrap();
+        assert_eq!(config, Config {
+            session: Some("from-file".to_owned()),
+            year: 2022,
+            cache_dir: PathBuf::from("inputs"),
+            ..Config::default()
+        });
+
+        let config = Config::load(env(&[
+            ("AOC_CONFIG", file), ("AOC_SESSION", "from-env"), ("AOC_BASE_URL", "http://stub"),
+        ])).unwrap();
+        assert_eq!(config.session.as_deref(), Some("from-env"));
+        assert_eq!((config.base_url.as_str(), config.year), ("http://stub", 2022));
+
+        // the default location, when there is nothing there
+        let config = Config::load(env(&[("HOME", dir.to_str().unwrap())])).unwrap();
+        assert_eq!(config, Config::default());
+
+        assert!(Config::parse("year = \"2023\"").is_err());
+        assert!(Config::parse("token = \"abc\"").is_err());
+    }
+}
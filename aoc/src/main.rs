@@ -0,0 +1,25 @@
+use aoc::{Config, Fetcher, HttpBackend};
+use std::env;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let usage = format!("Usage: {} fetch <day>", args[0]);
+    let day: u32 = match &args[1..] {
+        [command, day] if command == "fetch" => match day.parse() {
+            Ok(d) => d,
+            Err(_) => { eprintln!("{}", usage); return },
+        },
+        _ => { eprintln!("{}", usage); return },
+    };
+
+    let config = match Config::load(|name| env::var(name).ok()) {
+        Ok(c) => c,
+        Err(e) => { eprintln!("{}", e); std::process::exit(1) },
+    };
+
+    match Fetcher::new(config, HttpBackend::default()).fetch(day) {
+        Ok(path) => println!("{}", path.display()),
+        Err(e) => { eprintln!("{}", e); std::process::exit(1) },
+    }
+}
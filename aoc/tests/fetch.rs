@@ -0,0 +1,116 @@
+//! Runs `aoc fetch` against a stub HTTP server on the loopback interface.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Request line and cookie of each request the stub received.
+type Requests = Arc<Mutex<Vec<(String, String)>>>;
+
+/// Serves `body` at `/2023/day/1/input`, and 404 anywhere else.
+fn stub_server(body: &'static str) -> (String, Requests) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Requests::default();
+
+    let seen = requests.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&stream);
+
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            let mut cookie = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() { break }
+                if let Some(c) = line.strip_prefix("Cookie: ") { cookie = c.trim().to_owned() }
+            }
+            let request = request.trim().to_owned();
+
+            let response = match request.as_str() {
+                "GET /2023/day/1/input HTTP/1.1" => format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(), body),
+                _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_owned(),
+            };
+            seen.lock().unwrap().push((request, cookie));
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (base_url, requests)
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc-fetch-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn fetch(day: &str, dir: &Path, env: &[(&str, &str)]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["fetch", day])
+        .env_clear()
+        .env("AOC_CONFIG", dir.join("config.toml"))
+        .env("AOC_CACHE_DIR", dir.join("cache"))
+        .envs(env.iter().copied())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn fetches_and_caches() {
+    let (base_url, requests) = stub_server("1abc2\npqr3stu8vwx\n");
+    let dir = temp_dir("cache");
+    let env = [("AOC_BASE_URL", base_url.as_str()), ("AOC_SESSION", "53cr37")];
+
+    for _ in 0..2 {
+        let output = fetch("1", &dir, &env);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let path = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(Path::new(path.trim()), dir.join("cache/2023/day-01"));
+        assert_eq!(std::fs::read_to_string(path.trim()).unwrap(), "1abc2\npqr3stu8vwx\n");
+    }
+
+    // the second run found the input in the cache
+    assert_eq!(*requests.lock().unwrap(),
+               [("GET /2023/day/1/input HTTP/1.1".to_owned(), "session=53cr37".to_owned())]);
+}
+
+#[test]
+fn http_errors_are_not_cached() {
+    let (base_url, requests) = stub_server("");
+    let dir = temp_dir("error");
+    let env = [("AOC_BASE_URL", base_url.as_str()), ("AOC_SESSION", "53cr37")];
+
+    let output = fetch("2", &dir, &env);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("HTTP status 404"));
+    assert!(!dir.join("cache/2023/day-02").exists());
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn session_from_config_file() {
+    let (base_url, requests) = stub_server("seeds: 79 14\n");
+    let dir = temp_dir("config");
+    std::fs::write(dir.join("config.toml"), "session = \"from-file\"\n").unwrap();
+
+    let output = fetch("1", &dir, &[("AOC_BASE_URL", base_url.as_str())]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(requests.lock().unwrap()[0].1, "session=from-file");
+
+    // no session at all
+    std::fs::remove_file(dir.join("config.toml")).unwrap();
+    let output = fetch("3", &dir, &[("AOC_BASE_URL", base_url.as_str())]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no session token"));
+}
@@ -0,0 +1,65 @@
+//! Runs a day binary on its committed input and compares what it prints
+//! with the answers recorded in its `answers.toml`, for the integration
+//! tests of every day.
+
+use std::path::Path;
+use std::process::Command;
+
+fn answers(dir: &Path) -> toml::Table {
+    std::fs::read_to_string(dir.join("answers.toml"))
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+fn run(bin: &str, dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(bin)
+        .args(args)
+        .arg(dir.join("input"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Parses stdout as a single `{"<key>":<number or null>,...}` line,
+/// timing and unsolved parts left out.
+fn json_report(output: &str) -> toml::Value {
+    assert_eq!(output.lines().count(), 1, "not a single JSON line: {:?}", output);
+    let printed: toml::Table = output.trim()
+        .strip_prefix('{').unwrap()
+        .strip_suffix('}').unwrap()
+        .split(',')
+        .map(|f| f.split_once(':').unwrap())
+        .map(|(k, v)| (k.trim_matches('"').to_owned(), v))
+        .filter(|(k, v)| k != "elapsed_ms" && *v != "null")
+        .map(|(k, v)| (k, toml::Value::Integer(v.parse().unwrap())))
+        .collect();
+    toml::Value::Table(printed)
+}
+
+/// Checks both the text output and the `--format json` report of `bin`,
+/// the binary of the crate in `manifest_dir`.
+pub fn check(bin: &str, manifest_dir: &str) {
+    let dir = Path::new(manifest_dir);
+    let mut answers = answers(dir);
+    let json = answers.remove("json").unwrap();
+
+    // "<label>: <answer>" lines
+    let printed: toml::Table = run(bin, dir, &[])
+        .lines()
+        .filter_map(|l| l.split_once(": "))
+        .map(|(k, v)| (k.to_owned(), toml::Value::Integer(v.trim().parse().unwrap())))
+        .collect();
+    assert_eq!(printed, answers);
+    assert_eq!(json_report(&run(bin, dir, &["--format", "json"])), json);
+}
+
+/// Checks that with `--format json`, the output `args` ask for besides
+/// the answers stays off stdout, which holds the report alone.
+pub fn check_json(bin: &str, manifest_dir: &str, args: &[&str]) {
+    let dir = Path::new(manifest_dir);
+    let json = answers(dir).remove("json").unwrap();
+    let args: Vec<&str> = ["--format", "json"].iter().chain(args).copied().collect();
+    assert_eq!(json_report(&run(bin, dir, &args)), json);
+}
@@ -0,0 +1,173 @@
+//! Output shared by the day binaries.
+//!
+//! With `--format json`, every binary prints a single line following the
+//! same schema:
+//!
+//! ```text
+//! {"day":5,"part1":null,"part2":100165128,"elapsed_ms":12.345}
+//! ```
+//!
+//! where a part is `null` when the binary doesn't solve it. Any other
+//! output, such as reports asked for on the command line, then goes to
+//! stderr through `diag!` and `diagln!`, leaving the line alone on stdout.
+//!
+//! With `--time`, the wall-clock time of each phase goes to stderr.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "answers")]
+pub mod answers;
+
+/// Prints output besides the answers: to stdout in text mode, and to
+/// stderr in JSON mode.
+#[macro_export]
+macro_rules! diag {
+    ($format:expr, $($arg:tt)*) => {
+        match $format {
+            $crate::Format::Text => print!($($arg)*),
+            $crate::Format::Json => eprint!($($arg)*),
+        }
+    };
+}
+
+/// Like `diag!`, with a newline.
+#[macro_export]
+macro_rules! diagln {
+    ($format:expr) => {
+        $crate::diagln!($format, "")
+    };
+    ($format:expr, $($arg:tt)*) => {
+        match $format {
+            $crate::Format::Text => println!($($arg)*),
+            $crate::Format::Json => eprintln!($($arg)*),
+        }
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// The labelled answers each binary prints by default.
+    Text,
+    Json,
+}
+
+impl Format {
+    pub fn parse(value: &str) -> Option<Format> {
+        match value {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Answers of a day, as printed by `--format json`.
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    pub day: u32,
+    // decimal digits, so that any integer type fits
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub elapsed: Duration,
+}
+
+impl Report {
+    pub fn new(day: u32, elapsed: Duration) -> Self {
+        Report { day, part1: None, part2: None, elapsed }
+    }
+
+    /// Sets the answer to the first part, which must be an integer.
+    pub fn part1(mut self, answer: impl fmt::Display) -> Self {
+        self.part1 = Some(answer.to_string());
+        self
+    }
+
+    /// Sets the answer to the second part, which must be an integer.
+    pub fn part2(mut self, answer: impl fmt::Display) -> Self {
+        self.part2 = Some(answer.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let part = |p: &Option<String>| p.clone().unwrap_or_else(|| "null".to_owned());
+        write!(f, "{{\"day\":{},\"part1\":{},\"part2\":{},\"elapsed_ms\":{:.3}}}",
+               self.day, part(&self.part1), part(&self.part2),
+               self.elapsed.as_secs_f64() * 1000.0)
+    }
+}
+
+/// Wall-clock time of the successive phases of a run.
+#[derive(Debug)]
+pub struct Timer {
+    last: Instant,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl Timer {
+    pub fn start() -> Self {
+        Timer { last: Instant::now(), phases: vec![] }
+    }
+
+    /// Ends a phase, which started with the previous one's end.
+    pub fn lap(&mut self, phase: &'static str) {
+        let now = Instant::now();
+        self.phases.push((phase, now - self.last));
+        self.last = now;
+    }
+
+    /// Time of the phases ended so far.
+    pub fn elapsed(&self) -> Duration {
+        self.phases.iter().map(|(_, d)| *d).sum()
+    }
+}
+
+impl fmt::Display for Timer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (phase, d) in &self.phases {
+            writeln!(f, "{:<12}{:>12.3} ms", phase, d.as_secs_f64() * 1000.0)?;
+        }
+        writeln!(f, "{:<12}{:>12.3} ms", "total", self.elapsed().as_secs_f64() * 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_schema() {
+        let report = Report::new(8, Duration::from_micros(1500)).part1(11309).part2(13740108158591u64);
+        assert_eq!(report.to_string(),
+                   r#"{"day":8,"part1":11309,"part2":13740108158591,"elapsed_ms":1.500}"#);
+
+        let report = Report::new(4, Duration::ZERO).part2(30u128);
+        assert_eq!(report.to_string(), r#"{"day":4,"part1":null,"part2":30,"elapsed_ms":0.000}"#);
+    }
+
+    #[test]
+    fn timer_phases() {
+        let mut timer = Timer::start();
+        timer.lap("parse");
+        std::thread::sleep(Duration::from_millis(2));
+        timer.lap("solve");
+
+        let phases: Vec<&str> = timer.phases.iter().map(|(p, _)| *p).collect();
+        assert_eq!(phases, ["parse", "solve"]);
+        assert!(timer.phases[1].1 >= Duration::from_millis(2));
+        assert_eq!(timer.elapsed(), timer.phases[0].1 + timer.phases[1].1);
+
+        let text = timer.to_string();
+        let names: Vec<&str> = text.lines().filter_map(|l| l.split_whitespace().next()).collect();
+        assert_eq!(names, ["parse", "solve", "total"]);
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(Format::parse("json"), Some(Format::Json));
+        assert_eq!(Format::parse("text"), Some(Format::Text));
+        assert_eq!(Format::parse("yaml"), None);
+    }
+}
@@ -0,0 +1,25 @@
+//! Vocabulary, parse and solve phases on the committed input, run
+//! separately.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_01::{calibration_sum, parse_document, Tokenizer, ENGLISH};
+use std::hint::black_box;
+
+fn phases(c: &mut Criterion) {
+    let input = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/input")).unwrap();
+    let words = Tokenizer::parse_vocabulary(ENGLISH).unwrap();
+    let mut group = c.benchmark_group("day-01");
+
+    group.bench_function("vocabulary", |b| b.iter(|| Tokenizer::new(black_box(&words))));
+
+    let tokenizer = Tokenizer::new(&words);
+    group.bench_function("parse", |b| b.iter(|| parse_document(&tokenizer, black_box(&input))));
+
+    let lines = parse_document(&tokenizer, &input);
+    group.bench_function("solve", |b| b.iter(|| calibration_sum(black_box(&lines))));
+
+    group.finish();
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
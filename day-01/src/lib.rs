@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+pub static ENGLISH: &str = "\
+zero 0
+one 1
+two 2
+three 3
+four 4
+five 5
+six 6
+seven 7
+eight 8
+nine 9
+";
+
+/// Digit or number word found in a line, with its position in chars.
+#[derive(Debug, PartialEq)]
+pub struct Token {
+    pub value: u32,
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Token {
+    pub fn text<'a>(&self, line: &'a str) -> &'a str {
+        let mut indices = line.char_indices().map(|(i, _)| i).chain([line.len()]);
+        let start = indices.nth(self.start).unwrap();
+        let end = indices.nth(self.len - 1).unwrap();
+        &line[start..end]
+    }
+}
+
+/// Aho-Corasick automaton over a number-word vocabulary and the plain
+/// digits, finding every match in a single pass, overlapping ones included.
+#[derive(Debug)]
+pub struct Tokenizer {
+    goto: Vec<HashMap<char, usize>>,
+    fail: Vec<usize>,
+    // (value, length) of the words ending at each state
+    out: Vec<Vec<(u32, usize)>>,
+}
+
+impl Tokenizer {
+    pub fn new(words: &[(String, u32)]) -> Self {
+        let mut tokenizer = Tokenizer { goto: vec![HashMap::new()], fail: vec![0], out: vec![vec![]] };
+
+        let digits = (0..10).map(|d| (d.to_string(), d));
+        for (word, value) in digits.chain(words.iter().cloned()) {
+            let mut state = 0;
+            for c in word.chars() {
+                state = match tokenizer.goto[state].get(&c) {
+                    Some(s) => *s,
+                    None => {
+                        let s = tokenizer.goto.len();
+                        tokenizer.goto.push(HashMap::new());
+                        tokenizer.fail.push(0);
+                        tokenizer.out.push(vec![]);
+                        tokenizer.goto[state].insert(c, s);
+                        s
+                    },
+                };
+            }
+            tokenizer.out[state].push((value, word.chars().count()));
+        }
+
+        // breadth-first, so failure links of shorter prefixes are ready
+        let mut queue: VecDeque<usize> = tokenizer.goto[0].values().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            let edges: Vec<(char, usize)> = tokenizer.goto[state]
+                .iter().map(|(c, s)| (*c, *s)).collect();
+            for (c, next) in edges {
+                let mut f = tokenizer.fail[state];
+                while f != 0 && !tokenizer.goto[f].contains_key(&c) {
+                    f = tokenizer.fail[f];
+                }
+                let f = tokenizer.goto[f].get(&c).copied().filter(|s| *s != next).unwrap_or(0);
+                tokenizer.fail[next] = f;
+                let inherited = tokenizer.out[f].clone();
+                tokenizer.out[next].extend(inherited);
+                queue.push_back(next);
+            }
+        }
+
+        tokenizer
+    }
+
+    /// Parses a vocabulary made of `<word> <value>` lines.
+    pub fn parse_vocabulary(contents: &str) -> Result<Vec<(String, u32)>, String> {
+        contents.lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| match l.split_whitespace().collect::<Vec<&str>>()[..] {
+                [word, value] => match value.parse::<u32>() {
+                    Ok(v) if v < 10 => Ok((word.to_owned(), v)),
+                    _ => Err(format!("line {}: value must be a digit: {}", i + 1, l)),
+                },
+                _ => Err(format!("line {}: expected <word> <value>: {}", i + 1, l)),
+            })
+            .collect()
+    }
+
+    /// Every token in the line, sorted by position.
+    pub fn tokenize(&self, line: &str) -> Vec<Token> {
+        let mut tokens = vec![];
+        let mut state = 0;
+        for (i, c) in line.chars().enumerate() {
+            while state != 0 && !self.goto[state].contains_key(&c) {
+                state = self.fail[state];
+            }
+            state = self.goto[state].get(&c).copied().unwrap_or(0);
+            for (value, len) in &self.out[state] {
+                tokens.push(Token { value: *value, start: i + 1 - len, len: *len });
+            }
+        }
+        tokens.sort_by_key(|t| (t.start, t.len));
+        tokens
+    }
+}
+
+/// Line of the calibration document, with its tokens.
+#[derive(Debug)]
+pub struct Line<'a> {
+    /// 1-based line number.
+    pub number: usize,
+    pub text: &'a str,
+    pub tokens: Vec<Token>,
+}
+
+impl Line<'_> {
+    /// The first and last digits as a two-digit number, if the line has
+    /// any digit.
+    pub fn value(&self) -> Option<u32> {
+        Some(self.tokens.first()?.value * 10 + self.tokens.last()?.value)
+    }
+}
+
+/// Tokenizes every non-empty line of the document.
+pub fn parse_document<'a>(tokenizer: &Tokenizer, contents: &'a str) -> Vec<Line<'a>> {
+    contents.split('\n')
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(i, text)| Line { number: i + 1, text, tokens: tokenizer.tokenize(text) })
+        .collect()
+}
+
+/// Sum of the calibration values, lines without digits left out.
+pub fn calibration_sum(lines: &[Line]) -> u32 {
+    lines.iter().filter_map(|l| l.value()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn english() -> Tokenizer {
+        Tokenizer::new(&Tokenizer::parse_vocabulary(ENGLISH).unwrap())
+    }
+
+    #[test]
+    fn overlapping_words() {
+        let tokens = english().tokenize("twone");
+        assert_eq!(tokens, [
+            Token { value: 2, start: 0, len: 3 },
+            Token { value: 1, start: 2, len: 3 },
+        ]);
+    }
+
+    #[test]
+    fn example_calibration() {
+        let document = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n\
+                        4nineeightseven2\nzoneight234\n7pqrstsixteen\n";
+        let lines = parse_document(&english(), document);
+        let values: Vec<u32> = lines.iter().filter_map(|l| l.value()).collect();
+        assert_eq!(values, [29, 83, 13, 24, 42, 14, 76]);
+        assert_eq!(calibration_sum(&lines), 281);
+    }
+
+    #[test]
+    fn token_text() {
+        let line = "xfünfzig3";
+        let tokens = Tokenizer::new(&[("fünf".to_owned(), 5)]).tokenize(line);
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text(line)).collect();
+        assert_eq!(texts, ["fünf", "3"]);
+    }
+
+    #[test]
+    fn custom_vocabulary() {
+        let words = Tokenizer::parse_vocabulary("drei 3\nfünf 5\nsechs 6\n").unwrap();
+        let tokens = Tokenizer::new(&words).tokenize("xfünfzigdreisechs");
+        let found: Vec<(u32, usize)> = tokens.iter().map(|t| (t.value, t.start)).collect();
+        assert_eq!(found, [(5, 1), (3, 8), (6, 12)]);
+
+        assert!(Tokenizer::parse_vocabulary("uno").is_err());
+        assert!(Tokenizer::parse_vocabulary("dieci 10").is_err());
+    }
+}
@@ -1,46 +1,68 @@
-use std::collections::HashMap;
+use common::{diagln, Format, Report, Timer};
+use day_01::{calibration_sum, parse_document, Tokenizer, ENGLISH};
 use std::env;
 use std::fs::File;
 use std::io::prelude::*;
 
-fn replace_leftmost<'a>(line: &'a str, trans: &HashMap<&'a str, &'a str>) -> String {
-    let mut running_idx: usize = line.len();
-    let mut running_line: String = line.to_string();
-    for (old, new) in trans {
-        match line.find(old) {
-            Some(r) => {
-                if r < running_idx {
-                    running_idx = r;
-                    running_line = line.replacen(old, new, 1).to_string();
-                }
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let usage = format!("Usage: {} [--vocab <file>] [--report] [--strict] [--format <text|json>] [--time] <input>", args[0]);
+    let mut vocab: Option<&String> = None;
+    let mut report = false;
+    let mut strict = false;
+    let mut format = Format::Text;
+    let mut time = false;
+    let mut input: Option<&String> = None;
+
+    let mut it = args.iter().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--vocab" => match it.next() {
+                Some(v) => vocab = Some(v),
+                None => { eprintln!("{}", usage); return },
             },
-            None => continue,
-        };
+            "--report" => report = true,
+            "--strict" => strict = true,
+            "--format" => match it.next().and_then(|v| Format::parse(v)) {
+                Some(f) => format = f,
+                None => { eprintln!("{}", usage); return },
+            },
+            "--time" => time = true,
+            _ if input.is_none() => input = Some(arg),
+            _ => { eprintln!("{}", usage); return },
+        }
+    }
+
+    let input = match input {
+        Some(i) => i,
+        None => { eprintln!("{}", usage); return },
     };
-    running_line
-}
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut timer = Timer::start();
 
-    let ltrans: HashMap<&str, &str> = HashMap::from([
-        ("zero", "0"), ("one", "1"), ("two", "2"), ("three", "3"), ("four", "4"),
-        ("five", "5"), ("six", "6"), ("seven", "7"), ("eight", "8"), ("nine", "9"),
-    ]);
-
-    let _rtrans:HashMap::<String, &str> = HashMap::from_iter(ltrans.iter().map(
-        |(key, value)| { (key.chars().rev().collect::<String>(), *value) }
-    ));
-    let rtrans: HashMap<&str, &str> = HashMap::from_iter(_rtrans.iter().map(
-        |(key, value)| { (key.as_str(), *value) }
-    ));
-
-    if args.len() != 2 {
-        eprintln!("Usage: {} <input>", args[0]);
-        return
+    let mut vocab_contents = ENGLISH.to_owned();
+    if let Some(path) = vocab {
+        vocab_contents = match std::fs::read_to_string(path) {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("{}", e);
+                return
+            },
+        };
     }
 
-    let mut file = match File::open(&args[1]) {
+    let words = match Tokenizer::parse_vocabulary(&vocab_contents) {
+        Ok(w) => w,
+        Err(e) => {
+            eprintln!("{}", e);
+            return
+        },
+    };
+    let tokenizer = Tokenizer::new(&words);
+    timer.lap("vocabulary");
+
+    let mut file = match File::open(input) {
         Ok(r) => r,
         Err(e) => {
             eprintln!("{}", e);
@@ -57,33 +79,45 @@ fn main() {
         },
     };
 
-    let mut sum: u32 = 0;
-    for line in contents.split('\n'){
+    timer.lap("read");
 
-        let replaced_leftmost = replace_leftmost(line, &ltrans);
+    let lines = parse_document(&tokenizer, &contents);
+    timer.lap("parse");
 
-        let first: u32 = match replaced_leftmost.find(|c: char| c.is_digit(10)) {
-            Some(r) => {
-                let chars = replaced_leftmost.chars().collect::<Vec<_>>();
-                chars[r].to_digit(10).unwrap()
+    let sum = calibration_sum(&lines);
+    timer.lap("solve");
+
+    let mut missing = 0;
+    for line in &lines {
+        let (first, last, value) = match (line.tokens.first(), line.tokens.last(), line.value()) {
+            (Some(f), Some(l), Some(v)) => (f, l, v),
+            _ => {
+                let level = if strict { "error" } else { "warning" };
+                eprintln!("{}: line {} has no digit: {:?}", level, line.number, line.text);
+                missing += 1;
+                continue
             },
-            None => continue,
         };
 
-        let rev_line = line.chars().rev().collect::<String>();
-        let rev_repl = replace_leftmost(rev_line.as_str(), &rtrans);
-        let replaced_rightmost = rev_repl.chars().rev().collect::<String>();
+        if report {
+            diagln!(format, "{:>5}  first {:>8} @{:<3} last {:>8} @{:<3} value {}", line.number,
+                    format!("{:?}", first.text(line.text)), first.start,
+                    format!("{:?}", last.text(line.text)), last.start, value);
+        }
+    }
 
-        let last: u32 = match replaced_rightmost.rfind(|c: char| c.is_digit(10)) {
-            Some(r) => {
-                let chars = replaced_rightmost.chars().collect::<Vec<_>>();
-                chars[r].to_digit(10).unwrap()
-            },
-            None => continue,
-        };
+    if strict && missing > 0 {
+        eprintln!("{} lines without digits", missing);
+        std::process::exit(1);
+    }
 
-        sum += first * 10 + last;
+    match format {
+        Format::Text => println!("sum: {:?} ", sum),
+        // number words are only read in the second part
+        Format::Json => println!("{}", Report::new(1, timer.elapsed()).part2(sum)),
     }
 
-    println!("sum: {:?} ", sum);
+    if time {
+        eprint!("{}", timer);
+    }
 }
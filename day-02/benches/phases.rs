@@ -0,0 +1,21 @@
+//! Parse and solve phases on the committed input, run separately.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_02::{game_sums, parse_games, Hand, MAX_BLUE, MAX_GREEN, MAX_RED};
+use std::hint::black_box;
+
+fn phases(c: &mut Criterion) {
+    let input = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/input")).unwrap();
+    let bag = Hand { red: MAX_RED, green: MAX_GREEN, blue: MAX_BLUE };
+    let mut group = c.benchmark_group("day-02");
+
+    group.bench_function("parse", |b| b.iter(|| parse_games(black_box(&input))));
+
+    let games = parse_games(&input);
+    group.bench_function("solve", |b| b.iter(|| game_sums(black_box(&games), &bag)));
+
+    group.finish();
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
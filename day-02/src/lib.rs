@@ -0,0 +1,256 @@
+use regex::Regex;
+use std::collections::BTreeMap;
+
+pub static MAX_RED:   i32 = 12;
+pub static MAX_GREEN: i32 = 13;
+pub static MAX_BLUE:  i32 = 14;
+
+pub static COLOURS: [&str; 3] = ["red", "green", "blue"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hand {
+    pub red:   i32,
+    pub green: i32,
+    pub blue:  i32,
+}
+
+impl Hand {
+    pub fn is_possible(&self, bag: &Hand) -> bool {
+        self.red <= bag.red && self.green <= bag.green && self.blue <= bag.blue
+    }
+
+    /// Colours of which there are more cubes than in the bag.
+    pub fn limiting_colours(&self, bag: &Hand) -> Vec<&'static str> {
+        let exceeding = [self.red > bag.red, self.green > bag.green, self.blue > bag.blue];
+        COLOURS.iter().zip(exceeding).filter(|(_, e)| *e).map(|(c, _)| *c).collect()
+    }
+
+    pub fn power(&self) -> i32 {
+        self.red * self.green * self.blue
+    }
+
+    pub fn parse_bag(value: &str) -> Option<Hand> {
+        let counts: Vec<i32> = value.split(',').map(|s| s.trim().parse().ok()).collect::<Option<_>>()?;
+        match counts[..] {
+            [red, green, blue] => Some(Hand { red, green, blue }),
+            _ => None,
+        }
+    }
+
+    fn collect_from_game(re_hand: &Regex, line: &str) -> Vec<Hand> {
+        re_hand.captures_iter(line).map(|c| {
+            let r = match c.name("red") {
+                Some(m) => m.as_str().parse::<i32>().unwrap(),
+                None => 0
+            };
+            let g = match c.name("green") {
+                Some(m) => m.as_str().parse::<i32>().unwrap(),
+                None => 0
+            };
+            let b = match c.name("blue") {
+                Some(m) => m.as_str().parse::<i32>().unwrap(),
+                None => 0
+            };
+            Hand { red: r, green: g, blue: b }
+        }).collect()
+    }
+
+    pub fn get_requirements(hands: &[Hand]) -> Hand {
+        let mut required = Hand { red: 0, green: 0, blue: 0 };
+        for hand in hands {
+            required.red   = i32::max(hand.red,   required.red);
+            required.green = i32::max(hand.green, required.green);
+            required.blue  = i32::max(hand.blue,  required.blue);
+        }
+        required
+    }
+}
+
+/// What the games admitted by a bag must reach, in the inverse search.
+#[derive(Debug)]
+pub enum BagTarget {
+    Games(i32),
+    IdSum(i32),
+}
+
+/// Smallest bags, none containing another, admitting enough of the
+/// games, given as pairs of id and requirements.
+pub fn pareto_bags(games: &[(i32, Hand)], target: &BagTarget) -> Vec<Hand> {
+    let (weight, goal): (fn(i32) -> i32, i32) = match target {
+        BagTarget::Games(n) => (|_| 1, *n),
+        BagTarget::IdSum(s) => (|id| id, *s),
+    };
+
+    // an optimal bag only ever holds as many cubes as some game needs
+    let candidates = |count: fn(&Hand) -> i32| {
+        let mut v: Vec<i32> = games.iter().map(|(_, h)| count(h)).chain([0]).collect();
+        v.sort();
+        v.dedup();
+        v
+    };
+
+    let mut bags = vec![];
+    for red in candidates(|h| h.red) {
+        for green in candidates(|h| h.green) {
+            // fewest blue cubes reaching the goal with these red and green
+            let mut fitting: Vec<&(i32, Hand)> = games.iter()
+                .filter(|(_, h)| h.red <= red && h.green <= green)
+                .collect();
+            fitting.sort_by_key(|(_, h)| h.blue);
+
+            let mut reached = 0;
+            let mut blue = None;
+            if goal <= 0 { blue = Some(0) }
+            for (id, h) in fitting {
+                if blue.is_some() { break }
+                reached += weight(*id);
+                if reached >= goal { blue = Some(h.blue) }
+            }
+
+            if let Some(blue) = blue {
+                bags.push(Hand { red, green, blue });
+            }
+        }
+    }
+
+    non_dominated(bags)
+}
+
+/// Bags containing no other one, sorted and with equal bags kept once.
+fn non_dominated(mut bags: Vec<Hand>) -> Vec<Hand> {
+    // once sorted, a bag can only contain the ones before it: the sweep
+    // keeps their fewest blue cubes for each count of green ones, as a
+    // staircase where blue decreases as green grows
+    bags.sort_by_key(|b| (b.red, b.green, b.blue));
+    let mut stairs = BTreeMap::<i32, i32>::new();
+    let mut pareto = vec![];
+    for bag in bags {
+        if stairs.range(..=bag.green).next_back().is_some_and(|(_, blue)| *blue <= bag.blue) {
+            continue;
+        }
+        let covered: Vec<i32> = stairs.range(bag.green..)
+            .take_while(|(_, blue)| **blue >= bag.blue)
+            .map(|(green, _)| *green)
+            .collect();
+        for green in covered {
+            stairs.remove(&green);
+        }
+        stairs.insert(bag.green, bag.blue);
+        pareto.push(bag);
+    }
+    pareto
+}
+
+/// Matches the hands of a game, capturing the count of each colour.
+fn hand_regex() -> Regex {
+    let _r = r"\s*((?<red>\d+) red)\s*";
+    let _g = r"\s*((?<green>\d+) green)\s*";
+    let _b = r"\s*((?<blue>\d+) blue)\s*";
+    Regex::new(format!(
+        r"(?<hand>({}|{}|{}),?)+(;|$)", _r, _g, _b).as_str()).unwrap()
+}
+
+/// Hands of every game, by game id.
+pub fn parse_games(contents: &str) -> Vec<(i32, Vec<Hand>)> {
+    let re_id = Regex::new(r"^Game (?<id>\d+):").unwrap();
+    let re_hand = hand_regex();
+
+    contents.split('\n')
+        .filter_map(|line| {
+            let id = re_id.captures(line)?["id"].parse::<i32>().unwrap();
+            Some((id, Hand::collect_from_game(&re_hand, line)))
+        })
+        .collect()
+}
+
+/// Sum of the ids of the games possible with `bag`, and sum of the
+/// powers of every game's requirements.
+pub fn game_sums(games: &[(i32, Vec<Hand>)], bag: &Hand) -> (i32, i32) {
+    games.iter().fold((0, 0), |(sum, power_sum), (id, hands)| {
+        let possible = hands.iter().all(|h| h.is_possible(bag));
+        (sum + if possible { *id } else { 0 }, power_sum + Hand::get_requirements(hands).power())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limiting_colours() {
+        let bag = Hand::parse_bag("12,13,14").unwrap();
+        let req = Hand { red: 20, green: 13, blue: 15 };
+        assert_eq!(req.limiting_colours(&bag), ["red", "blue"]);
+        assert!(Hand { red: 12, green: 13, blue: 14 }.limiting_colours(&bag).is_empty());
+        assert!(Hand::parse_bag("12,13").is_none());
+    }
+
+    #[test]
+    fn inverse_bag_search() {
+        let games = vec![
+            (1, Hand { red: 4, green: 2, blue: 6 }),
+            (2, Hand { red: 1, green: 3, blue: 4 }),
+            (3, Hand { red: 20, green: 13, blue: 6 }),
+        ];
+
+        let bags = pareto_bags(&games, &BagTarget::Games(2));
+        assert_eq!(bags, [Hand { red: 4, green: 3, blue: 6 }]);
+
+        let bags = pareto_bags(&games, &BagTarget::Games(1));
+        assert_eq!(bags, [Hand { red: 1, green: 3, blue: 4 }, Hand { red: 4, green: 2, blue: 6 }]);
+
+        let bags = pareto_bags(&games, &BagTarget::IdSum(2));
+        assert_eq!(bags, [Hand { red: 1, green: 3, blue: 4 }]);
+
+        assert!(pareto_bags(&games, &BagTarget::IdSum(7)).is_empty());
+    }
+
+    #[test]
+    fn dominated_and_equal_bags() {
+        let bag = |red, green, blue| Hand { red, green, blue };
+        // copies of two incomparable bags, among bags containing them
+        let mut bags = vec![];
+        for i in 0..500 {
+            bags.push(bag(3, 5, 2));
+            bags.push(bag(1, 6, 4));
+            bags.push(bag(3 + i % 3, 5 + i % 5, 2 + i % 7));
+            bags.push(bag(1 + i % 4, 6, 4 + i % 2));
+        }
+        bags.reverse();
+        assert_eq!(non_dominated(bags), [bag(1, 6, 4), bag(3, 5, 2)]);
+
+        // equal on two colours, told apart by the third
+        let bags = vec![bag(2, 2, 3), bag(2, 3, 2), bag(2, 2, 2), bag(3, 2, 2), bag(2, 2, 2)];
+        assert_eq!(non_dominated(bags), [bag(2, 2, 2)]);
+        let bags = vec![bag(1, 9, 9), bag(9, 1, 9), bag(9, 9, 1), bag(5, 5, 5)];
+        assert_eq!(non_dominated(bags), [bag(1, 9, 9), bag(5, 5, 5), bag(9, 1, 9), bag(9, 9, 1)]);
+
+        let games: Vec<(i32, Hand)> = (1..=1000).map(|id| (id, bag(4, 2, 6))).collect();
+        assert_eq!(pareto_bags(&games, &BagTarget::Games(1000)), [bag(4, 2, 6)]);
+    }
+
+    #[test]
+    fn example_games() {
+        let example = [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
+        ];
+        let bag = Hand { red: MAX_RED, green: MAX_GREEN, blue: MAX_BLUE };
+
+        let games = parse_games(&example.join("\n"));
+        assert_eq!(games.iter().map(|(id, _)| *id).collect::<Vec<i32>>(), [1, 2, 3, 4, 5]);
+        let required: Vec<Hand> = games.iter()
+            .map(|(_, hands)| Hand::get_requirements(hands))
+            .collect();
+        assert_eq!(required[0], Hand { red: 4, green: 2, blue: 6 });
+
+        let possible: Vec<bool> = required.iter().map(|h| h.is_possible(&bag)).collect();
+        assert_eq!(possible, [true, true, false, false, true]);
+        let powers: Vec<i32> = required.iter().map(|h| h.power()).collect();
+        assert_eq!(powers, [48, 12, 1560, 630, 36]);
+        assert_eq!(game_sums(&games, &bag), (8, 2286));
+    }
+}
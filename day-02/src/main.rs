@@ -1,62 +1,57 @@
-use regex::Regex;
+use common::{diagln, Format, Report, Timer};
+use day_02::{game_sums, parse_games, pareto_bags, BagTarget, Hand, COLOURS, MAX_BLUE, MAX_GREEN, MAX_RED};
 use std::env;
 use std::fs::File;
 use std::io::prelude::*;
 
-static MAX_RED:   i32 = 12;
-static MAX_GREEN: i32 = 13;
-static MAX_BLUE:  i32 = 14;
-
-#[derive(Debug)]
-struct Hand {
-    red:   i32,
-    green: i32,
-    blue:  i32,
-}
-
-impl Hand {
-    fn is_possible(&self) -> bool {
-        self.red <= MAX_RED && self.green <= MAX_GREEN && self.blue <= MAX_BLUE
-    }
+fn main() {
+    let args: Vec<String> = env::args().collect();
 
-    fn collect_from_game(re_hand: &Regex, line: &str) -> Vec<Hand> {
-        re_hand.captures_iter(line).map(|c| {
-            let r = match c.name("red") {
-                Some(m) => m.as_str().parse::<i32>().unwrap(),
-                None => 0
-            };
-            let g = match c.name("green") {
-                Some(m) => m.as_str().parse::<i32>().unwrap(),
-                None => 0
-            };
-            let b = match c.name("blue") {
-                Some(m) => m.as_str().parse::<i32>().unwrap(),
-                None => 0
-            };
-            Hand { red: r, green: g, blue: b }
-        }).collect()
-    }
+    let usage = format!(
+        "Usage: {} [--bag <red,green,blue>] [--report] [--min-games <n> | --min-id-sum <n>] \
+         [--format <text|json>] [--time] <input>",
+        args[0]);
+    let mut bag = Hand { red: MAX_RED, green: MAX_GREEN, blue: MAX_BLUE };
+    let mut target: Option<BagTarget> = None;
+    let mut report = false;
+    let mut format = Format::Text;
+    let mut time = false;
+    let mut input: Option<&String> = None;
 
-    fn get_requirements(hands: &Vec<Hand>) -> Hand {
-        let mut required = Hand { red: 0, green: 0, blue: 0 };
-        for hand in hands {
-            required.red   = i32::max(hand.red,   required.red);
-            required.green = i32::max(hand.green, required.green);
-            required.blue  = i32::max(hand.blue,  required.blue);
+    let mut it = args.iter().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--bag" => match it.next().and_then(|v| Hand::parse_bag(v)) {
+                Some(b) => bag = b,
+                None => { eprintln!("{}", usage); return },
+            },
+            "--report" => report = true,
+            "--format" => match it.next().and_then(|v| Format::parse(v)) {
+                Some(f) => format = f,
+                None => { eprintln!("{}", usage); return },
+            },
+            "--time" => time = true,
+            "--min-games" => match it.next().and_then(|v| v.parse().ok()) {
+                Some(n) => target = Some(BagTarget::Games(n)),
+                None => { eprintln!("{}", usage); return },
+            },
+            "--min-id-sum" => match it.next().and_then(|v| v.parse().ok()) {
+                Some(n) => target = Some(BagTarget::IdSum(n)),
+                None => { eprintln!("{}", usage); return },
+            },
+            _ if input.is_none() => input = Some(arg),
+            _ => { eprintln!("{}", usage); return },
         }
-        required
     }
-}
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+    let input = match input {
+        Some(i) => i,
+        None => { eprintln!("{}", usage); return },
+    };
 
-    if args.len() != 2 {
-        eprintln!("Usage: {} <input>", args[0]);
-        return
-    }
+    let mut timer = Timer::start();
 
-    let mut file = match File::open(&args[1]) {
+    let mut file = match File::open(input) {
         Ok(r) => r,
         Err(e) => {
             eprintln!("{}", e);
@@ -72,31 +67,70 @@ fn main() {
             return
         },
     };
+    timer.lap("read");
+
+    let games = parse_games(&contents);
+    timer.lap("parse");
 
-    let re_id = Regex::new(r"^Game (?<id>\d+):").unwrap();
-    let _r = r"\s*((?<red>\d+) red)\s*";
-    let _g = r"\s*((?<green>\d+) green)\s*";
-    let _b = r"\s*((?<blue>\d+) blue)\s*";
-    let re_hand = Regex::new(format!(
-        r"(?<hand>({}|{}|{}),?)+(;|$)", _r, _g, _b).as_str()).unwrap();
-
-    let mut sum: i32 = 0;
-    let mut power_sum: i32 = 0;
-    for line in contents.split('\n') {
-        let id = match re_id.captures(line) {
-            Some(r) => r["id"].parse::<i32>().unwrap(),
-            None => continue,
-        };
-
-        let hands = Hand::collect_from_game(&re_hand, line);
-        if hands.iter().all(|h| h.is_possible()) {
-            sum += id;
+    let (sum, power_sum) = game_sums(&games, &bag);
+    timer.lap("solve");
+
+    let requirements: Vec<(i32, Hand)> = games.iter()
+        .map(|(id, hands)| (*id, Hand::get_requirements(hands)))
+        .collect();
+
+    if report {
+        diagln!(format, "{:>5} {:>5} {:>5} {:>5} {:>8}  {:<8} limiting",
+                "id", "red", "green", "blue", "power", "possible");
+
+        let mut impossible = 0;
+        let mut limiting_count = [0; 3];
+        for (id, req) in &requirements {
+            // the game fits the bag exactly when its requirements do
+            let limiting = req.limiting_colours(&bag);
+            if !limiting.is_empty() {
+                impossible += 1;
+            }
+            for (k, colour) in COLOURS.iter().enumerate() {
+                if limiting.contains(colour) { limiting_count[k] += 1; }
+            }
+            diagln!(format, "{:>5} {:>5} {:>5} {:>5} {:>8}  {:<8} {}", id, req.red, req.green,
+                    req.blue, req.power(), limiting.is_empty(), limiting.join(","));
+        }
+
+        diagln!(format);
+        diagln!(format, "impossible games: {}", impossible);
+        for (colour, count) in COLOURS.iter().zip(limiting_count) {
+            let share = if impossible > 0 { 100.0 * count as f64 / impossible as f64 } else { 0.0 };
+            diagln!(format, "limited by {:<5}: {:>4} ({:.1}%)", colour, count, share);
+        }
+        timer.lap("report");
+    }
+
+    if let Some(target) = &target {
+        let bags = pareto_bags(&requirements, target);
+        diagln!(format, "pareto-optimal bags for {:?}:", target);
+        for b in &bags {
+            diagln!(format, "{:>5} red {:>5} green {:>5} blue, {:>5} total",
+                    b.red, b.green, b.blue, b.red + b.green + b.blue);
+        }
+        match bags.iter().min_by_key(|b| b.red + b.green + b.blue) {
+            Some(b) => diagln!(format, "minimum total: {} ({} red, {} green, {} blue)",
+                               b.red + b.green + b.blue, b.red, b.green, b.blue),
+            None => diagln!(format, "minimum total: none, target not reachable"),
         }
+        timer.lap("pareto");
+    }
 
-        let req = Hand::get_requirements(&hands);
-        power_sum += req.red * req.green * req.blue;
+    match format {
+        Format::Text => {
+            println!("sum: {:?} ", sum);
+            println!("power sum: {:?} ", power_sum);
+        },
+        Format::Json => println!("{}", Report::new(2, timer.elapsed()).part1(sum).part2(power_sum)),
     }
 
-    println!("sum: {:?} ", sum);
-    println!("power sum: {:?} ", power_sum);
+    if time {
+        eprint!("{}", timer);
+    }
 }
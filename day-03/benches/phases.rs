@@ -0,0 +1,27 @@
+//! Parse and solve phases on the committed input, run separately.
+//!
+//! Parsing already computes the sums, which the solve phase recomputes
+//! from scratch.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_03::Schematic;
+use std::hint::black_box;
+
+fn phases(c: &mut Criterion) {
+    let input = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/input")).unwrap();
+    let mut group = c.benchmark_group("day-03");
+
+    group.bench_function("parse", |b| b.iter(|| Schematic::parse(black_box(&input), true).unwrap()));
+
+    let schematic = Schematic::parse(&input, true).unwrap();
+    group.bench_function("solve", |b| b.iter(|| {
+        let schematic = black_box(&schematic);
+        (schematic.part_numbers_sum(), schematic.gear_ratios_sum())
+    }));
+    group.bench_function("components", |b| b.iter(|| black_box(&schematic).components().len()));
+
+    group.finish();
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
@@ -0,0 +1,607 @@
+use grid::{Grid, Rect};
+use regex::Regex;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::OnceLock;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct Token<T> {
+    pub tok: T,
+    x0: usize, x1: usize, y: usize,
+}
+pub type Num = Token<i32>;
+type Sym = Token<char>;
+
+/// Digits runs, shared by parsing and edits.
+fn number_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // ASCII digits only, as \d would also match other scripts' digits
+    RE.get_or_init(|| Regex::new(r"[0-9]+").unwrap())
+}
+
+/// Token columns are char indices: `columns` maps each byte offset of
+/// the line, as reported by the regex, to the index of its char.
+trait FromMatch {
+    fn from_match(m: regex::Match, y: usize, columns: &[usize]) -> Self;
+}
+
+impl FromMatch for Num {
+    fn from_match(m: regex::Match, y: usize, columns: &[usize]) -> Self {
+        let tok = m.as_str().parse::<i32>().unwrap();
+        let x0 = columns[m.start()];
+        let x1 = columns[m.end()];
+        Num { tok, x0, x1, y }
+    }
+}
+
+impl FromMatch for Sym {
+    fn from_match(m: regex::Match, y: usize, columns: &[usize]) -> Self {
+        let tok = m.as_str().chars().next().unwrap();
+        let x0 = columns[m.start()];
+        let x1 = columns[m.end()];
+        Sym { tok, x0, x1, y }
+    }
+}
+
+/// Problems found parsing a schematic, with 1-based line and column.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// A row is shorter than the longest one, and padding is disabled.
+    RaggedRow { line: usize, width: usize, expected: usize },
+    /// Whitespace or control characters, which are neither digits,
+    /// symbols nor empty cells.
+    UnexpectedChar { line: usize, column: usize, c: char },
+    /// A number whose value does not fit in a token.
+    TooLarge { line: usize, column: usize, digits: String },
+    /// An edit of a cell, at 0-based column `x` of row `y`, outside the grid.
+    OutOfBounds { x: usize, y: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::RaggedRow { line, width, expected } =>
+                write!(f, "{}: row is {} cells wide, expected {}", line, width, expected),
+            ParseError::UnexpectedChar { line, column, c } =>
+                write!(f, "{}:{}: unexpected character {:?}", line, column, c),
+            ParseError::TooLarge { line, column, digits } =>
+                write!(f, "{}:{}: number {} is too large", line, column, digits),
+            ParseError::OutOfBounds { x, y } =>
+                write!(f, "[{}, {}] is out of the schematic", y, x),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Schematic {
+    gears: Vec<Sym>,
+    numbers: Vec<Num>,
+    number_layer: Grid<Option<usize>>,
+    symbol_layer: Grid<Option<char>>,
+    // totals kept up to date by `set_cell`
+    part_sum: i64,
+    gear_sum: i64,
+}
+
+/// Numbers and symbols connected through each other's covered rectangle.
+#[derive(Debug)]
+pub struct Component {
+    pub numbers: Vec<usize>,
+    // (x, y, symbol)
+    pub symbols: Vec<(usize, usize, char)>,
+    pub bbox: Rect,
+    pub sum: i64,
+}
+
+impl Schematic {
+    /// Parses the grid, padding rows shorter than the longest one with
+    /// empty cells unless `pad` is false, in which case they are errors.
+    pub fn parse(contents: &str, pad: bool) -> Result<Schematic, Vec<ParseError>> {
+        let re_num = number_regex();
+        // anything but digits, so numbers and symbols never share a cell
+        let re_sym = Regex::new(r"[^\.0-9]").unwrap();
+
+        let mut gears: Vec<Sym> = vec![];
+        let mut numbers: Vec<Num> = vec![];
+        let mut number_rows = vec![];
+        let mut symbol_rows = vec![];
+        let mut errors = vec![];
+
+        let lines: Vec<(usize, &str)> = contents.split('\n')
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+            .enumerate()
+            .filter(|(_, l)| !l.is_empty())
+            .collect();
+        let width = lines.iter().map(|(_, l)| l.chars().count()).max().unwrap_or(0);
+
+        for (j, (line_idx, line)) in lines.into_iter().enumerate() {
+            let columns = char_columns(line);
+            let line_width = columns[line.len()];
+            if line_width != width && !pad {
+                errors.push(ParseError::RaggedRow { line: line_idx + 1, width: line_width, expected: width });
+            }
+
+            for (i, c) in line.chars().enumerate() {
+                if c.is_whitespace() || c.is_control() {
+                    errors.push(ParseError::UnexpectedChar { line: line_idx + 1, column: i + 1, c });
+                }
+            }
+
+            let too_large: Vec<ParseError> = re_num.find_iter(line)
+                .filter(|m| m.as_str().parse::<i32>().is_err())
+                .map(|m| ParseError::TooLarge {
+                    line: line_idx + 1, column: columns[m.start()] + 1, digits: m.as_str().to_owned(),
+                })
+                .collect();
+            if !too_large.is_empty() {
+                errors.extend(too_large);
+                continue;
+            }
+
+            let nums: Vec<Num> = get_tokens(re_num, j, line, &columns);
+            let syms: Vec<Sym> = get_tokens(&re_sym, j, line, &columns);
+
+            let numbers_count = numbers.len();
+            let mut nmask = vec![std::option::Option::None; width];
+            for (k, t) in nums.iter().enumerate() {
+                for cell in &mut nmask[t.x0..t.x1] {
+                    let idx = numbers_count + k;
+                    *cell = Some(idx);
+                }
+            }
+
+            let mut smask = vec![None; width];
+            for t in &syms {
+                for cell in &mut smask[t.x0..t.x1] {
+                    *cell = Some(t.tok);
+                }
+                if t.tok == '*' {
+                    gears.push(t.to_owned());
+                }
+            }
+
+            numbers.extend(nums);
+            number_rows.push(nmask);
+            symbol_rows.push(smask);
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        // rows are all padded to the same width
+        let number_layer = Grid::from_rows(number_rows).unwrap();
+        let symbol_layer = Grid::from_rows(symbol_rows).unwrap();
+        let mut schematic = Schematic {
+            gears, numbers, number_layer, symbol_layer, part_sum: 0, gear_sum: 0,
+        };
+        schematic.part_sum = schematic.part_numbers_sum();
+        schematic.gear_sum = schematic.gear_ratios_sum();
+        Ok(schematic)
+    }
+
+    fn covered_rectangle<T>(&self, n: &Token<T>) -> Rect {
+        let token = Rect { x0: n.x0, y0: n.y, x1: n.x1, y1: n.y + 1 };
+        token.expand(1, self.symbol_layer.width(), self.symbol_layer.height())
+    }
+
+    fn adjacent_to_symbol(&self, n: &Num) -> bool {
+        let rect = self.covered_rectangle(n);
+        self.symbol_layer.view(rect).any(|(_, _, s)| s.is_some())
+    }
+
+    fn get_numbers_on_rectangle(&self, rect: Rect) -> Vec<&Num> {
+        let mut s = HashSet::new();
+        let mut v = vec![];
+
+        for (_, _, cell) in self.number_layer.view(rect) {
+            match *cell {
+                Some(idx) => {
+                    if !s.contains(&idx) {
+                        s.insert(idx);
+                        v.push(&self.numbers[idx]);
+                    }
+                },
+                None => continue
+            };
+        }
+        v
+    }
+
+    fn get_adjacent_numbers(&self, s: &Sym) -> Vec<&Num> {
+        let rect = self.covered_rectangle(s);
+        self.get_numbers_on_rectangle(rect)
+    }
+
+    fn gear_ratio(&self, g: &Sym) -> i64 {
+        match self.get_adjacent_numbers(g)[..] {
+            [a, b] => a.tok as i64 * b.tok as i64,
+            _ => 0,
+        }
+    }
+
+    pub fn numbers(&self) -> &[Num] {
+        &self.numbers
+    }
+
+    pub fn symbol_layer(&self) -> &Grid<Option<char>> {
+        &self.symbol_layer
+    }
+
+    /// Sum of part numbers, as kept up to date by `set_cell`.
+    pub fn part_sum(&self) -> i64 {
+        self.part_sum
+    }
+
+    /// Sum of gear ratios, as kept up to date by `set_cell`.
+    pub fn gear_sum(&self) -> i64 {
+        self.gear_sum
+    }
+
+    pub fn part_numbers_sum(&self) -> i64 {
+        self.get_part_numbers().map(|p| p.tok as i64).sum()
+    }
+
+    pub fn gear_ratios_sum(&self) -> i64 {
+        self.gears.iter().map(|g| self.gear_ratio(g)).sum()
+    }
+
+    /// Part numbers and gear ratios which an edit of the cell at `x`, `y`
+    /// may change, given the digits run `lo..hi` on its row it may touch.
+    fn local_sums(&self, x: usize, y: usize, lo: usize, hi: usize) -> (i64, i64) {
+        let (width, height) = (self.symbol_layer.width(), self.symbol_layer.height());
+        let around = Rect { x0: x, y0: y, x1: x + 1, y1: y + 1 }.expand(1, width, height);
+        let run = Rect { x0: lo, y0: y, x1: hi, y1: y + 1 };
+
+        let mut seen = HashSet::new();
+        let parts: i64 = self.number_layer.view(run).chain(self.number_layer.view(around))
+            .filter_map(|(_, _, cell)| *cell)
+            .filter(|idx| seen.insert(*idx))
+            .map(|idx| &self.numbers[idx])
+            .filter(|n| self.adjacent_to_symbol(n))
+            .map(|n| n.tok as i64)
+            .sum();
+
+        let gears: i64 = self.gears.iter()
+            .filter(|g| around.y0 <= g.y && g.y < around.y1 && g.x0 + 1 >= lo && g.x0 <= hi)
+            .map(|g| self.gear_ratio(g))
+            .sum();
+
+        (parts, gears)
+    }
+
+    /// Groups numbers and symbols into machine parts: connected components
+    /// with at least a symbol, sorted by position.
+    pub fn components(&self) -> Vec<Component> {
+        let (width, height) = (self.symbol_layer.width(), self.symbol_layer.height());
+
+        // union-find over numbers, followed by every cell for symbols
+        let n = self.numbers.len();
+        let mut parent: Vec<usize> = (0..n + width * height).collect();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        let node = |i: usize, j: usize| match self.number_layer[(i, j)] {
+            Some(idx) => Some(idx),
+            None => self.symbol_layer[(i, j)].map(|_| n + j * width + i),
+        };
+
+        let mut tokens: Vec<(usize, Rect)> = self.numbers.iter().enumerate()
+            .map(|(idx, num)| (idx, self.covered_rectangle(num)))
+            .collect();
+        for (i, j, c) in self.symbol_layer.iter() {
+            if let Some(c) = c {
+                let sym = Sym { tok: *c, x0: i, x1: i + 1, y: j };
+                tokens.push((n + j * width + i, self.covered_rectangle(&sym)));
+            }
+        }
+        for (a, rect) in &tokens {
+            for (i, j) in rect.coords() {
+                if let Some(b) = node(i, j) {
+                    let (ra, rb) = (find(&mut parent, *a), find(&mut parent, b));
+                    parent[ra] = rb;
+                }
+            }
+        }
+
+        let mut groups: HashMap<usize, Component> = HashMap::new();
+        for (a, _) in &tokens {
+            let root = find(&mut parent, *a);
+            let (x0, x1, y) = match a.checked_sub(n) {
+                None => (self.numbers[*a].x0, self.numbers[*a].x1, self.numbers[*a].y),
+                Some(cell) => (cell % width, cell % width + 1, cell / width),
+            };
+            let token = Rect { x0, y0: y, x1, y1: y + 1 };
+            let c = groups.entry(root).or_insert(Component {
+                numbers: vec![], symbols: vec![], sum: 0, bbox: token,
+            });
+            match a.checked_sub(n) {
+                None => {
+                    c.numbers.push(*a);
+                    c.sum += self.numbers[*a].tok as i64;
+                },
+                Some(_) => c.symbols.push((x0, y, self.symbol_layer[(x0, y)].unwrap())),
+            }
+            c.bbox = c.bbox.union(&token);
+        }
+
+        let mut components: Vec<Component> = groups.into_values()
+            .filter(|c| !c.symbols.is_empty())
+            .collect();
+        components.sort_by_key(|c| (c.bbox.y0, c.bbox.x0));
+        components
+    }
+
+    fn remove_number(&mut self, idx: usize) {
+        let n = self.numbers.swap_remove(idx);
+        for cell in &mut self.number_layer.row_mut(n.y).unwrap()[n.x0..n.x1] {
+            *cell = None;
+        }
+        // the last number took its place
+        if let Some(moved) = self.numbers.get(idx) {
+            for cell in &mut self.number_layer.row_mut(moved.y).unwrap()[moved.x0..moved.x1] {
+                *cell = Some(idx);
+            }
+        }
+    }
+
+    /// Changes the cell at column `x` of row `y`, updating tokens, layers
+    /// and totals around it only.
+    pub fn set_cell(&mut self, x: usize, y: usize, ch: char) -> Result<(), ParseError> {
+        if ch.is_whitespace() || ch.is_control() {
+            return Err(ParseError::UnexpectedChar { line: y + 1, column: x + 1, c: ch });
+        }
+        let width = self.symbol_layer.width();
+        if x >= width || y >= self.symbol_layer.height() {
+            return Err(ParseError::OutOfBounds { x, y });
+        }
+
+        // the digits run on the row which the edit may change, split or
+        // merge, as adjacent numbers join when a digit is set between them
+        let mut run: Vec<usize> = [x.wrapping_sub(1), x, x + 1].iter()
+            .filter(|i| **i < width)
+            .filter_map(|i| self.number_layer[(*i, y)])
+            .collect();
+        run.dedup();
+        let lo = run.iter().map(|idx| self.numbers[*idx].x0).fold(x, usize::min);
+        let hi = run.iter().map(|idx| self.numbers[*idx].x1).fold(x + 1, usize::max);
+
+        let (parts_before, gears_before) = self.local_sums(x, y, lo, hi);
+
+        let mut digits = vec!['.'; hi - lo];
+        for idx in &run {
+            let n = &self.numbers[*idx];
+            // keep leading zeros, which the value alone loses
+            let text = format!("{:0w$}", n.tok, w = n.x1 - n.x0);
+            for (i, c) in text.chars().enumerate() {
+                digits[n.x0 - lo + i] = c;
+            }
+        }
+        digits[x - lo] = if ch.is_ascii_digit() { ch } else { '.' };
+
+        // joined runs may not fit a token: reject the edit before any change
+        let text: String = digits.iter().collect();
+        let mut merged = vec![];
+        for m in number_regex().find_iter(&text) {
+            let tok = m.as_str().parse().map_err(|_| ParseError::TooLarge {
+                line: y + 1, column: lo + m.start() + 1, digits: m.as_str().to_owned(),
+            })?;
+            merged.push(Num { tok, x0: lo + m.start(), x1: lo + m.end(), y });
+        }
+
+        // indices shift as numbers are removed, so find them again
+        while let Some(idx) = (lo..hi).find_map(|i| self.number_layer[(i, y)]) {
+            self.remove_number(idx);
+        }
+        for n in merged {
+            let idx = self.numbers.len();
+            for cell in &mut self.number_layer.row_mut(y).unwrap()[n.x0..n.x1] {
+                *cell = Some(idx);
+            }
+            self.numbers.push(n);
+        }
+
+        self.gears.retain(|g| (g.x0, g.y) != (x, y));
+        if ch == '*' {
+            self.gears.push(Sym { tok: ch, x0: x, x1: x + 1, y });
+        }
+        self.symbol_layer[(x, y)] = Some(ch).filter(|c| *c != '.' && !c.is_ascii_digit());
+
+        let (parts_after, gears_after) = self.local_sums(x, y, lo, hi);
+        self.part_sum += parts_after - parts_before;
+        self.gear_sum += gears_after - gears_before;
+        Ok(())
+    }
+
+    fn get_part_numbers(&self) -> impl Iterator<Item = &Num> {
+        self.numbers.iter().filter(|n|
+            self.adjacent_to_symbol(n)
+        )
+    }
+}
+
+fn get_tokens<T: FromMatch>(re: &Regex, y: usize, line: &str, columns: &[usize]) -> Vec<T> {
+    re.captures_iter(line).map(|c| {
+        let m = c.get(0).unwrap();
+        T::from_match(m, y, columns)
+    }).collect()
+}
+
+/// Maps each byte offset of the line, and the one past its end, to the
+/// index of the char it falls in.
+fn char_columns(line: &str) -> Vec<usize> {
+    let mut columns = vec![0; line.len() + 1];
+    for (i, (b, c)) in line.char_indices().enumerate() {
+        for col in &mut columns[b..b + c.len_utf8()] {
+            *col = i;
+        }
+    }
+    columns[line.len()] = line.chars().count();
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+    #[test]
+    fn example_sums() {
+        let schematic = Schematic::parse(EXAMPLE, false).unwrap();
+        assert_eq!(schematic.part_numbers_sum(), 4361);
+        assert_eq!(schematic.gear_ratios_sum(), 467835);
+    }
+
+    #[test]
+    fn multi_byte_symbols_keep_columns() {
+        // same as the example, with some symbols replaced by § and €
+        let example = EXAMPLE.replace('#', "§").replace(['$', '+'], "€");
+        let schematic = Schematic::parse(&example, false).unwrap();
+        assert_eq!(schematic.part_numbers_sum(), 4361);
+        assert_eq!(schematic.gear_ratios_sum(), 467835);
+        assert_eq!(schematic.number_layer.width(), 10);
+        assert_eq!(schematic.numbers[3].x0, 6);
+    }
+
+    #[test]
+    fn multi_byte_symbol_at_end_of_line() {
+        // with byte offsets, € would be out of the 5-cell row
+        let schematic = Schematic::parse("..12€\n.....\n..€3.\n", false).unwrap();
+        assert_eq!(schematic.part_numbers_sum(), 15);
+        assert_eq!(schematic.symbol_layer.row(0).unwrap(), [None, None, None, None, Some('€')]);
+        assert_eq!((schematic.numbers[1].x0, schematic.numbers[1].x1), (3, 4));
+    }
+
+    #[test]
+    fn ragged_rows_are_padded() {
+        let schematic = Schematic::parse("467..114\n...*\n..35..633.\n", true).unwrap();
+        assert_eq!(schematic.symbol_layer.width(), 10);
+        assert_eq!(schematic.part_numbers_sum(), 467 + 35);
+
+        assert_eq!(Schematic::parse("467..114\n...*\n..35..633.\n", false).unwrap_err(), [
+            ParseError::RaggedRow { line: 1, width: 8, expected: 10 },
+            ParseError::RaggedRow { line: 2, width: 4, expected: 10 },
+        ]);
+    }
+
+    #[test]
+    fn unexpected_chars() {
+        let errors = Schematic::parse("12.\n.\t*\r\n", true).unwrap_err();
+        assert_eq!(errors, [ParseError::UnexpectedChar { line: 2, column: 2, c: '\t' }]);
+    }
+
+    #[test]
+    fn set_cell_updates_sums() {
+        let mut schematic = Schematic::parse(EXAMPLE, false).unwrap();
+        // 35 and 633 stop being part numbers, and the gear loses 35
+        schematic.set_cell(3, 1, '.').unwrap();
+        assert_eq!(schematic.part_sum, 4361 - 467 - 35);
+        assert_eq!(schematic.gear_sum, 467835 - 467 * 35);
+        // 633 becomes 6633, next to a gear with 755
+        schematic.set_cell(5, 2, '6').unwrap();
+        schematic.set_cell(5, 3, '*').unwrap();
+        assert_eq!(schematic.part_sum, 4361 - 467 - 35 - 633 + 6633);
+        assert_eq!(schematic.gear_sum, 467835 - 467 * 35);
+        assert!(schematic.set_cell(0, 0, ' ').is_err());
+        assert_eq!(schematic.set_cell(10, 0, '*'), Err(ParseError::OutOfBounds { x: 10, y: 0 }));
+        assert_eq!(schematic.set_cell(0, 10, '*'), Err(ParseError::OutOfBounds { x: 0, y: 10 }));
+        assert_eq!((schematic.part_sum(), schematic.gear_sum()), (4361 - 467 - 35 - 633 + 6633, 467835 - 467 * 35));
+    }
+
+    #[test]
+    fn numbers_too_large() {
+        let errors = Schematic::parse("1.\n.*99999999999\n", true).unwrap_err();
+        assert_eq!(errors, [ParseError::TooLarge { line: 2, column: 3, digits: "99999999999".to_owned() }]);
+
+        // joining 21474 and 83647 gives a number above i32::MAX
+        let mut schematic = Schematic::parse("21474.83647\n*..........\n", false).unwrap();
+        assert_eq!(schematic.set_cell(5, 0, '9'), Err(ParseError::TooLarge {
+            line: 1, column: 1, digits: "21474983647".to_owned(),
+        }));
+        assert_eq!(schematic.numbers.len(), 2);
+        assert_eq!(schematic.part_sum, 21474);
+        schematic.set_cell(5, 0, '#').unwrap();
+        assert_eq!(schematic.part_sum, 21474 + 83647);
+    }
+
+    #[test]
+    fn set_cell_matches_rebuild() {
+        let mut grid: Vec<Vec<char>> = EXAMPLE.lines().map(|l| l.chars().collect()).collect();
+        let mut schematic = Schematic::parse(EXAMPLE, false).unwrap();
+        let alphabet = ['.', '.', '.', '0', '1', '5', '9', '*', '*', '#', '€'];
+
+        let mut seed: u64 = 7;
+        let mut random = |n: usize| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % n
+        };
+
+        for _ in 0..2000 {
+            let (x, y, c) = (random(10), random(10), alphabet[random(alphabet.len())]);
+            grid[y][x] = c;
+            schematic.set_cell(x, y, c).unwrap();
+
+            let text: String = grid.iter().map(|r| r.iter().collect::<String>() + "\n").collect();
+            let rebuilt = Schematic::parse(&text, false).unwrap();
+            assert_eq!(schematic.part_sum, rebuilt.part_sum, "{}", text);
+            assert_eq!(schematic.gear_sum, rebuilt.gear_sum, "{}", text);
+
+            let spans = |s: &Schematic| {
+                let mut v: Vec<(usize, usize, usize, i32)> = s.numbers.iter()
+                    .map(|n| (n.y, n.x0, n.x1, n.tok)).collect();
+                v.sort();
+                v
+            };
+            assert_eq!(spans(&schematic), spans(&rebuilt), "{}", text);
+            assert_eq!(schematic.symbol_layer, rebuilt.symbol_layer);
+        }
+    }
+
+    #[test]
+    fn example_components() {
+        let schematic = Schematic::parse(EXAMPLE, false).unwrap();
+        let components = schematic.components();
+        let summary: Vec<(i64, String)> = components.iter()
+            .map(|c| (c.sum, c.symbols.iter().map(|(_, _, s)| s).collect()))
+            .collect();
+        assert_eq!(summary, [
+            (467 + 35, "*".to_owned()),
+            (633, "#".to_owned()),
+            (617, "*".to_owned()),
+            (592, "+".to_owned()),
+            (755 + 598, "*".to_owned()),
+            (664, "$".to_owned()),
+        ]);
+        assert_eq!(components[0].bbox, Rect { x0: 0, y0: 0, x1: 4, y1: 3 });
+
+        // a symbol between them joins the 617 and 592 parts into one
+        let schematic = Schematic::parse(&EXAMPLE.replace("617*.", "617*&"), false).unwrap();
+        let (numbers, symbols) = schematic.components().iter()
+            .map(|c| (c.numbers.len(), c.symbols.len()))
+            .max().unwrap();
+        assert_eq!((numbers, symbols), (2, 3));
+    }
+
+    #[test]
+    fn char_columns_of_multi_byte_line() {
+        assert_eq!(char_columns("a§1"), [0, 1, 1, 2, 3]);
+    }
+}
@@ -1,133 +1,45 @@
-use regex::Regex;
-use std::collections::HashSet;
+use common::{diagln, Format, Report, Timer};
+use day_03::Schematic;
 use std::env;
 use std::fs::File;
 use std::io::prelude::*;
 
-#[derive(Debug)]
-struct Layer<T> {
-    m: Vec<Vec<T>>
-}
-
-#[derive(Clone)]
-#[derive(Debug)]
-struct Token<T> {
-    tok: T,
-    x0: usize, x1: usize, y: usize,
-}
-type Num = Token<i32>;
-type Sym = Token<char>;
-
-trait FromMatch {
-    fn from_match(m: regex::Match, y: usize) -> Self;
-}
-
-impl FromMatch for Num {
-    fn from_match(m: regex::Match, y: usize) -> Self {
-        let tok = m.as_str().parse::<i32>().unwrap();
-        let x0 = m.start();
-        let x1 = m.end();
-        Num { tok, x0, x1, y }
-    }
-}
-
-impl FromMatch for Sym {
-    fn from_match(m: regex::Match, y: usize) -> Self {
-        let tok = m.as_str().chars().nth(0).unwrap();
-        let x0 = m.start();
-        let x1 = m.end();
-        Sym { tok, x0, x1, y }
-    }
-}
-
-#[derive(Debug)]
-struct Schematic {
-    gears: Vec<Sym>,
-    numbers: Vec<Num>,
-    number_layer: Layer<Option<usize>>,
-    symbol_layer: Layer<bool>,
-}
-
-#[derive(Debug)]
-struct Rectangle {
-    tl_x: usize, tl_y: usize,
-    br_x: usize, br_y: usize,
-}
-
-impl Rectangle {
-    fn to_coords_yx(&self) -> Vec<(usize, usize)> {
-        let mut v = vec![];
-        for j in self.tl_y..self.br_y {
-            for i in self.tl_x..self.br_x {
-                v.push((j, i));
-            }
-        }
-        v
-    }
-}
-
-impl Schematic {
-    fn covered_rectangle<T>(&self, n: &Token<T>) -> Rectangle {
-        let x_min = if n.x0 == 0 { 0 } else { n.x0 - 1 };
-        let x_max = usize::min(n.x1 + 1, self.symbol_layer.m[0].len());
-        let y_min = if n.y == 0 { 0 } else { n.y - 1 };
-        let y_max = usize::min(n.y + 2, self.symbol_layer.m.len());
-        Rectangle { tl_x: x_min, tl_y: y_min, br_x: x_max, br_y: y_max }
-    }
-
-    fn adjacent_to_symbol(&self, n: &Num) -> bool {
-        let rect = self.covered_rectangle(n);
-        rect.to_coords_yx().iter().any(|(j, i)|
-            self.symbol_layer.m[*j][*i]
-        )
-    }
-
-    fn get_numbers_on_rectangle(&self, rect: Rectangle) -> Vec<&Num> {
-        let mut s = HashSet::new();
-        let mut v = vec![];
+fn main() {
+    let args: Vec<String> = env::args().collect();
 
-        for (y, x) in rect.to_coords_yx() {
-            match self.number_layer.m[y][x] {
-                Some(idx) => {
-                    if !s.contains(&idx) {
-                        s.insert(idx);
-                        v.push(&self.numbers[idx]);
-                    }
-                },
-                None => continue
-            };
+    let usage = format!("Usage: {} [--strict] [--edit] [--components] [--format <text|json>] [--time] <input>",
+                        args[0]);
+    let mut strict = false;
+    let mut components = false;
+    let mut edit = false;
+    let mut format = Format::Text;
+    let mut time = false;
+    let mut input: Option<&String> = None;
+
+    let mut it = args.iter().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--strict" => strict = true,
+            "--edit" => edit = true,
+            "--components" => components = true,
+            "--format" => match it.next().and_then(|v| Format::parse(v)) {
+                Some(f) => format = f,
+                None => { eprintln!("{}", usage); return },
+            },
+            "--time" => time = true,
+            _ if input.is_none() => input = Some(arg),
+            _ => { eprintln!("{}", usage); return },
         }
-        v
     }
 
-    fn get_adjacent_numbers(&self, s: &Sym) -> Vec<&Num> {
-        let rect = self.covered_rectangle(s);
-        self.get_numbers_on_rectangle(rect)
-    }
-
-    fn get_part_numbers(&self) -> impl Iterator<Item = &Num> {
-        self.numbers.iter().filter(|n|
-            self.adjacent_to_symbol(n)
-        ).into_iter()
-    }
-}
-
-fn get_tokens<T: FromMatch>(re: &Regex, y: usize, line: &str) -> Vec<T> {
-    re.captures_iter(line).map(|c| {
-        let m = c.get(0).unwrap();
-        T::from_match(m, y)
-    }).collect()
-}
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
+    let input = match input {
+        Some(i) => i,
+        None => { eprintln!("{}", usage); return },
+    };
 
-    if args.len() != 2 {
-        eprintln!("Usage: {} <input>", args[0]);
-        return
-    }
+    let mut timer = Timer::start();
 
-    let mut file = match File::open(&args[1]) {
+    let mut file = match File::open(input) {
         Ok(r) => r,
         Err(e) => { eprintln!("{}", e); return },
     };
@@ -137,65 +49,70 @@ fn main() {
         Ok(r) => r,
         Err(e) => { eprintln!("{}", e); return },
     };
+    timer.lap("read");
 
-    let re_num = Regex::new(r"\d+").unwrap();
-    let re_sym = Regex::new(r"[^\.\d]").unwrap();
-
-    let mut gears: Vec<Sym> = vec![];
-    let mut numbers: Vec<Num> = vec![];
-    let mut number_layer = Layer { m: vec![] };
-    let mut symbol_layer = Layer { m: vec![] };
-
-    for (j, line) in contents.split('\n').into_iter().enumerate() {
-        if line.len() == 0 {
-            continue;
-        }
-
-        let nums: Vec<Num> = get_tokens(&re_num, j, &line);
-        let syms: Vec<Sym> = get_tokens(&re_sym, j, &line);
-        //println!("{}  {} nums, {} syms", line, nums.len(), syms.len());
-
-        let numbers_count = numbers.len();
-        let mut nmask = vec![std::option::Option::None; line.len()];
-        for (k, t) in nums.iter().enumerate() {
-            for i in t.x0..t.x1 {
-                let idx = numbers_count + k;
-                nmask[i] = Some(idx);
+    let mut schematic = match Schematic::parse(&contents, !strict) {
+        Ok(s) => s,
+        Err(errors) => {
+            for e in errors {
+                eprintln!("{}", e);
             }
-        }
-
-        let mut smask = vec![false; line.len()];
-        for t in &syms {
-            for i in t.x0..t.x1 {
-                smask[i] = true;
-                assert!(match nmask[i] {
-                    Some(_) => false,
-                    None => true
-                } , "[{}, {}] was number; ambiguous {:?}", j, i, t);
-            }
-            if t.tok == '*' {
-                gears.push(t.to_owned());
+            return
+        },
+    };
+    // the sums are kept by the schematic, so solved along with parsing
+    timer.lap("parse");
+
+    if edit {
+        // "<x> <y> <char>" edits from stdin, each followed by the new sums
+        for line in std::io::stdin().lock().lines() {
+            let line = match line {
+                Ok(l) => l,
+                Err(e) => { eprintln!("{}", e); return },
+            };
+            let parts: Vec<&str> = line.split(' ').collect();
+            let cell = match parts[..] {
+                [x, y, c] => x.parse::<usize>().ok()
+                    .zip(y.parse::<usize>().ok())
+                    .zip(c.chars().next().filter(|_| c.chars().count() == 1)),
+                _ => None,
+            };
+            let ((x, y), c) = match cell {
+                Some(cell) => cell,
+                None => { eprintln!("Expected <x> <y> <char>: {}", line); continue },
+            };
+            match schematic.set_cell(x, y, c) {
+                Ok(()) => diagln!(format, "{} {}", schematic.part_sum(), schematic.gear_sum()),
+                Err(e) => eprintln!("{}", e),
             }
         }
-
-        numbers.extend(nums);
-        number_layer.m.push(nmask);
-        symbol_layer.m.push(smask);
+        timer.lap("edit");
     }
 
-    let schematic = Schematic { gears, numbers, number_layer, symbol_layer };
+    if components {
+        for c in schematic.components() {
+            let numbers: Vec<String> = c.numbers.iter()
+                .map(|idx| schematic.numbers()[*idx].tok.to_string()).collect();
+            let symbols: String = c.symbols.iter().map(|(_, _, s)| s).collect();
+            diagln!(format, "[{}, {}]-[{}, {}] sum {}: symbols {} numbers {}",
+                    c.bbox.y0, c.bbox.x0, c.bbox.y1 - 1, c.bbox.x1 - 1,
+                    c.sum, symbols, numbers.join(" "));
+        }
+        timer.lap("components");
+    }
 
-    let pn_sum: i32 = schematic
-        .get_part_numbers()
-        .map(|p| p.tok)
-        .sum();
+    let pn_sum = schematic.part_sum();
+    let gear_sum = schematic.gear_sum();
 
-    let gear_sum: i32 = schematic.gears.iter()
-        .map(|g| schematic.get_adjacent_numbers(g))
-        .filter(|ns| ns.len() == 2)
-        .map(|ns| ns[0].tok * ns[1].tok)
-        .sum();
+    match format {
+        Format::Text => {
+            println!("part numbers sum: {:?}", pn_sum);
+            println!("gear ratios sum: {:?}", gear_sum);
+        },
+        Format::Json => println!("{}", Report::new(3, timer.elapsed()).part1(pn_sum).part2(gear_sum)),
+    }
 
-    println!("part numbers sum: {:?}", pn_sum);
-    println!("gear ratios sum: {:?}", gear_sum);
+    if time {
+        eprint!("{}", timer);
+    }
 }
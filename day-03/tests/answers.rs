@@ -0,0 +1,13 @@
+//! Runs the binary on the committed input and compares what it prints
+//! with the answers recorded in `answers.toml`.
+
+#[test]
+fn input_answers() {
+    common::answers::check(env!("CARGO_BIN_EXE_day-03"), env!("CARGO_MANIFEST_DIR"));
+}
+
+#[test]
+fn json_alone_on_stdout() {
+    common::answers::check_json(env!("CARGO_BIN_EXE_day-03"), env!("CARGO_MANIFEST_DIR"),
+                                &["--components", "--edit", "--time"]);
+}
@@ -0,0 +1,31 @@
+//! Parse and solve phases on the committed input, run separately.
+//!
+//! The binary streams cards, parsing and counting each one in turn, so
+//! the phases are timed here over all cards at once.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_04::{parse_card, Card, CardCounter};
+use std::hint::black_box;
+
+fn phases(c: &mut Criterion) {
+    let input = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/input")).unwrap();
+    let mut group = c.benchmark_group("day-04");
+
+    group.bench_function("parse", |b| b.iter(|| {
+        black_box(&input).lines().filter_map(parse_card).collect::<Vec<Card>>()
+    }));
+
+    let cards: Vec<Card> = input.lines().filter_map(parse_card).collect();
+    group.bench_function("solve", |b| b.iter(|| {
+        let mut counter = CardCounter::default();
+        for card in black_box(&cards) {
+            counter.add(card);
+        }
+        counter.total
+    }));
+
+    group.finish();
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
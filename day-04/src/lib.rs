@@ -0,0 +1,329 @@
+use regex::Regex;
+use std::collections::HashSet;
+use std::fmt;
+use std::io;
+use std::io::prelude::*;
+use std::sync::OnceLock;
+
+/// Extra copies won for the cards following the current one, kept in a
+/// ring buffer as long as the most matches a card can have.
+#[derive(Debug)]
+struct CardCopies {
+    extra: Vec<u128>,
+    head: usize,
+}
+
+impl CardCopies {
+    fn new() -> Self {
+        CardCopies { extra: vec![], head: 0 }
+    }
+
+    /// Makes room for cards winning up to `size` following ones.
+    fn reserve(&mut self, size: usize) {
+        if size > self.extra.len() {
+            self.extra.rotate_left(self.head);
+            self.head = 0;
+            self.extra.resize(size, 0);
+        }
+    }
+
+    /// Copies of the current card, the original included, moving on to
+    /// the next one.
+    fn next(&mut self) -> u128 {
+        if self.extra.is_empty() {
+            return 1;
+        }
+        let copies = 1 + std::mem::take(&mut self.extra[self.head]);
+        self.head = (self.head + 1) % self.extra.len();
+        copies
+    }
+
+    /// Adds `copies` to each of the `count` cards after the current one.
+    fn add(&mut self, count: usize, copies: u128) {
+        let len = self.extra.len();
+        for i in 0..count {
+            self.extra[(self.head + i) % len] += copies;
+        }
+    }
+}
+
+/// Numbers of a card, as listed, duplicates included.
+#[derive(Debug)]
+pub struct Card {
+    /// None when the id doesn't fit in a `u32`.
+    pub id: Option<u32>,
+    pub winning: Vec<u32>,
+    pub owned: Vec<u32>,
+}
+
+/// Parses a `Card <id>: <winning> | <owned>` line.
+pub fn parse_card(line: &str) -> Option<Card> {
+    // captures named groups:
+    // - "c", matching the card number
+    // - "w", matching winning numbers
+    // - "o", matching owned numbers
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"^Card\s*(?<c>\d+):(?<w>[\s\d]+)\|(?<o>[\s\d]+)$").unwrap());
+
+    let c = re.captures(line)?;
+    let numbers = |s: &str| -> Vec<u32> {
+        s.split(' ').filter_map(|n| n.parse().ok()).collect()
+    };
+    Some(Card { id: c["c"].parse().ok(), winning: numbers(&c["w"]), owned: numbers(&c["o"]) })
+}
+
+/// Running total of cards, originals and copies, fed one card at a time.
+#[derive(Debug)]
+pub struct CardCounter {
+    copies: CardCopies,
+    pub total: u128,
+}
+
+impl Default for CardCounter {
+    fn default() -> Self {
+        CardCounter { copies: CardCopies::new(), total: 0 }
+    }
+}
+
+impl CardCounter {
+    pub fn add(&mut self, card: &Card) {
+        let win_nums: HashSet<&u32> = card.winning.iter().collect();
+        let own_nums: HashSet<&u32> = card.owned.iter().collect();
+
+        // a card can't match more numbers than the winning ones
+        self.copies.reserve(win_nums.len());
+
+        let card_copies = self.copies.next();
+        self.total += card_copies;
+
+        let matching_numbers = HashSet::intersection(&win_nums, &own_nums).count();
+        self.copies.add(matching_numbers, card_copies);
+    }
+}
+
+/// Problems found validating cards, with 1-based line numbers.
+#[derive(Debug, PartialEq)]
+pub enum CardIssue {
+    /// The line doesn't look like a card, or its id doesn't fit in a
+    /// `u32`: either way the line is skipped.
+    Malformed { line: usize },
+    /// A number appears more than once among the winning or owned ones.
+    Duplicate { line: usize, card: u32, number: u32, owned: bool },
+    /// The card has a different count of winning or owned numbers than
+    /// the first one.
+    CountMismatch { line: usize, card: u32, counts: (usize, usize), expected: (usize, usize) },
+    /// The card id doesn't follow the previous card's one.
+    NonSequential { line: usize, card: u32, expected: u32 },
+}
+
+impl fmt::Display for CardIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CardIssue::Malformed { line } =>
+                write!(f, "{}: malformed card", line),
+            CardIssue::Duplicate { line, card, number, owned } => {
+                let side = if *owned { "owned" } else { "winning" };
+                write!(f, "{}: card {} repeats {} number {}", line, card, side, number)
+            },
+            CardIssue::CountMismatch { line, card, counts, expected } =>
+                write!(f, "{}: card {} has {} winning and {} owned numbers, expected {} and {}",
+                       line, card, counts.0, counts.1, expected.0, expected.1),
+            CardIssue::NonSequential { line, card, expected } =>
+                write!(f, "{}: card {} found, expected card {}", line, card, expected),
+        }
+    }
+}
+
+/// Issues kept by a `Validator`, the following ones being only counted
+/// so that memory stays constant however many cards are read.
+pub const MAX_KEPT_ISSUES: usize = 100;
+
+/// Checks cards one at a time against the ones seen before.
+#[derive(Debug, Default)]
+pub struct Validator {
+    // winning and owned numbers on the first card
+    counts: Option<(usize, usize)>,
+    next_card: Option<u32>,
+    /// The first `MAX_KEPT_ISSUES` issues found.
+    pub issues: Vec<CardIssue>,
+    /// Issues found, kept or not.
+    pub issue_count: usize,
+}
+
+impl Validator {
+    fn report(&mut self, issue: CardIssue) {
+        self.issue_count += 1;
+        if self.issues.len() < MAX_KEPT_ISSUES {
+            self.issues.push(issue);
+        }
+    }
+
+    fn malformed(&mut self, line: usize) {
+        self.report(CardIssue::Malformed { line });
+    }
+
+    fn check(&mut self, line: usize, card: u32, win_nums: &[u32], own_nums: &[u32]) {
+        if let Some(expected) = self.next_card {
+            if card != expected {
+                self.report(CardIssue::NonSequential { line, card, expected });
+            }
+        }
+        // no card can follow u32::MAX, whose successor isn't a valid id
+        self.next_card = card.checked_add(1);
+
+        let counts = (win_nums.len(), own_nums.len());
+        match self.counts {
+            Some(expected) if counts != expected =>
+                self.report(CardIssue::CountMismatch { line, card, counts, expected }),
+            Some(_) => {},
+            None => self.counts = Some(counts),
+        }
+
+        for (nums, owned) in [(win_nums, false), (own_nums, true)] {
+            let mut seen = HashSet::new();
+            let mut reported = HashSet::new();
+            for n in nums {
+                if !seen.insert(n) && reported.insert(n) {
+                    self.report(CardIssue::Duplicate { line, card, number: *n, owned });
+                }
+            }
+        }
+    }
+}
+
+/// Total number of cards, originals and copies, reading one line at a
+/// time.
+pub fn count_cards(reader: impl BufRead, validator: &mut Validator) -> io::Result<u128> {
+    let mut counter = CardCounter::default();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        // a card whose id overflows is as malformed, so skipped, as any
+        // other line which isn't a card
+        let (card, id) = match parse_card(&line) {
+            Some(card @ Card { id: Some(id), .. }) => (card, id),
+            _ => { validator.malformed(i + 1); continue },
+        };
+        validator.check(i + 1, id, &card.winning, &card.owned);
+        counter.add(&card);
+    }
+
+    Ok(counter.total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::BufReader;
+
+    #[test]
+    fn example_cards() {
+        let example = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+        let mut validator = Validator::default();
+        assert_eq!(count_cards(example.as_bytes(), &mut validator).unwrap(), 30);
+        assert_eq!(validator.issues, []);
+    }
+
+    /// Generates cards on the fly, never holding more than one line.
+    struct Generator {
+        card: usize,
+        cards: usize,
+        line: Vec<u8>,
+        pos: usize,
+    }
+
+    impl Generator {
+        fn matches(card: usize) -> usize {
+            if card.is_multiple_of(7) { card % 5 } else { 0 }
+        }
+    }
+
+    impl Read for Generator {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pos == self.line.len() {
+                if self.card == self.cards { return Ok(0) }
+                self.card += 1;
+                let own: Vec<String> = (0..5)
+                    .map(|i| if i < Self::matches(self.card) { i + 1 } else { i + 50 })
+                    .map(|n| n.to_string())
+                    .collect();
+                self.line = format!("Card {}: 1 2 3 4 5 | {}\n", self.card, own.join(" ")).into_bytes();
+                self.pos = 0;
+            }
+            let n = usize::min(buf.len(), self.line.len() - self.pos);
+            buf[..n].copy_from_slice(&self.line[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn generated_cards() {
+        let cards = 100_000;
+        let generator = Generator { card: 0, cards, line: vec![], pos: 0 };
+        let total = count_cards(BufReader::new(generator), &mut Validator::default()).unwrap();
+
+        // the same count, keeping every card's copies in a map
+        let mut card_count = HashMap::<usize, u128>::new();
+        for card in 1..=cards {
+            let card_copies = 1 + card_count.get(&card).copied().unwrap_or(0);
+            card_count.insert(card, card_copies);
+            for next in (card + 1)..(card + Generator::matches(card) + 1) {
+                *card_count.entry(next).or_insert(0) += card_copies;
+            }
+        }
+        let expected: u128 = (1..=cards).map(|c| card_count[&c]).sum();
+
+        assert_eq!(total, expected);
+    }
+
+    #[test]
+    fn invalid_cards() {
+        let cards = "\
+Card 1: 41 48 83 | 83 86  6 83
+Card 2: 13 32 20 | 61 30 68 82
+Card 4: 1 21 1 1 | 69 82 63 72
+Card 5 41 92 73 | 59 84 76 51
+Card 5: 87 83 26 | 88 30 70 12
+";
+        let mut validator = Validator::default();
+        count_cards(cards.as_bytes(), &mut validator).unwrap();
+        assert_eq!(validator.issues, [
+            CardIssue::Duplicate { line: 1, card: 1, number: 83, owned: true },
+            CardIssue::NonSequential { line: 3, card: 4, expected: 3 },
+            CardIssue::CountMismatch { line: 3, card: 4, counts: (4, 4), expected: (3, 4) },
+            CardIssue::Duplicate { line: 3, card: 4, number: 1, owned: false },
+            CardIssue::Malformed { line: 4 },
+        ]);
+        assert_eq!(validator.issues[1].to_string(), "3: card 4 found, expected card 3");
+        assert_eq!(validator.issue_count, 5);
+    }
+
+    #[test]
+    fn issues_kept_are_bounded() {
+        let cards: String = (1..=1000).map(|c| format!("Card {}: 1 1 | 2 2\n", c)).collect();
+        let mut validator = Validator::default();
+        count_cards(cards.as_bytes(), &mut validator).unwrap();
+        assert_eq!(validator.issues.len(), MAX_KEPT_ISSUES);
+        assert_eq!(validator.issue_count, 2000);
+    }
+
+    #[test]
+    fn last_card_id() {
+        let cards = "Card 4294967295: 1 | 2\nCard 4294967296: 1 | 2\n";
+        let mut validator = Validator::default();
+        assert_eq!(count_cards(cards.as_bytes(), &mut validator).unwrap(), 1);
+        assert_eq!(validator.issues, [CardIssue::Malformed { line: 2 }]);
+    }
+}
@@ -1,72 +1,72 @@
-use regex::Regex;
-use std::collections::HashMap;
-use std::collections::HashSet;
+use common::{Format, Report, Timer};
+use day_04::{count_cards, Validator};
 use std::env;
 use std::fs::File;
-use std::io::prelude::*;
+use std::io::BufReader;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
-        eprintln!("Usage: {} <input>", args[0]);
-        return
+    let usage = format!("Usage: {} [--strict] [--format <text|json>] [--time] <input>", args[0]);
+    let mut strict = false;
+    let mut format = Format::Text;
+    let mut time = false;
+    let mut input: Option<&String> = None;
+
+    let mut it = args.iter().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--strict" => strict = true,
+            "--format" => match it.next().and_then(|v| Format::parse(v)) {
+                Some(f) => format = f,
+                None => { eprintln!("{}", usage); return },
+            },
+            "--time" => time = true,
+            _ if input.is_none() => input = Some(arg),
+            _ => { eprintln!("{}", usage); return },
+        }
     }
 
-    let mut file = match File::open(&args[1]) {
+    let input = match input {
+        Some(i) => i,
+        None => { eprintln!("{}", usage); return },
+    };
+
+    let mut timer = Timer::start();
+
+    let file = match File::open(input) {
         Ok(r) => r,
         Err(e) => { eprintln!("{}", e); return },
     };
 
-    let mut contents = String::new();
-    match file.read_to_string(&mut contents){
+    let mut validator = Validator::default();
+    let cards = match count_cards(BufReader::new(file), &mut validator) {
         Ok(r) => r,
         Err(e) => { eprintln!("{}", e); return },
     };
+    // cards are read, parsed and counted in a single pass
+    timer.lap("count");
 
-    // captures named groups:
-    // - "c", matching the card number
-    // - "w", matching winning numbers
-    // - "o", matching owned numbers
-    let re = Regex::new(r"^Card\s*(?<c>\d+):(?<w>[\s\d]+)\|(?<o>[\s\d]+)$").unwrap();
-
-    let mut card_count = HashMap::new();
-
-    for line in contents.split('\n') {
-        let c = match re.captures(line) {
-            Some(c) => c,
-            None => continue,
-        };
-
-        let card: usize = c["c"].parse().unwrap();
-
-        let card_copies = 1 + match card_count.get(&card) {
-            Some(n) => *n,
-            None => 0
-        };
-        card_count.insert(card, card_copies);
-
-        let win_nums: HashSet<i32> = c["w"]
-            .split(' ')
-            .filter_map(|s| s.parse().ok())
-            .collect();
-
-        let own_nums: HashSet<i32> = c["o"]
-            .split(' ')
-            .filter_map(|s| s.parse().ok())
-            .collect();
-
-        let matching_numbers = HashSet::intersection(&win_nums, &own_nums).count();
+    let level = if strict { "error" } else { "warning" };
+    for issue in &validator.issues {
+        eprintln!("{}: {}", level, issue);
+    }
+    let more = validator.issue_count - validator.issues.len();
+    if more > 0 {
+        eprintln!("{}: {} more issues", level, more);
+    }
+    if strict && validator.issue_count > 0 {
+        eprintln!("{} issues", validator.issue_count);
+        std::process::exit(1);
+    }
 
-        for next in (card + 1)..(card + matching_numbers + 1) {
-            let next_copies = match card_count.get(&next) {
-                Some(n) => *n,
-                None => 0
-            };
-            card_count.insert(next, card_copies + next_copies);
-        }
+    match format {
+        Format::Text => println!("cards: {:?}", cards),
+        // the scratchcard copies are only won in the second part
+        Format::Json => println!("{}", Report::new(4, timer.elapsed()).part2(cards)),
     }
 
-    let cards: i32 = card_count.values().sum();
-    println!("cards: {:?}", cards);
+    if time {
+        eprint!("{}", timer);
+    }
 }
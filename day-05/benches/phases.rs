@@ -0,0 +1,20 @@
+//! Parse and solve phases on the committed input, run separately.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_05::{lowest_location, parse_almanac};
+use std::hint::black_box;
+
+fn phases(c: &mut Criterion) {
+    let input = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/input")).unwrap();
+    let mut group = c.benchmark_group("day-05");
+
+    group.bench_function("parse", |b| b.iter(|| parse_almanac(black_box(&input))));
+
+    let (seed_ranges, almanac) = parse_almanac(&input);
+    group.bench_function("solve", |b| b.iter(|| lowest_location(&almanac, black_box(&seed_ranges))));
+
+    group.finish();
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
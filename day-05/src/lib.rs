@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct AlmanacRange {
+    start: i64,
+    count: i64
+}
+
+impl AlmanacRange {
+    fn split(&self, left_cut: i64, right_cut: i64) ->
+            (Option<AlmanacRange>, Option<AlmanacRange>, Option<AlmanacRange>) {
+        let lstart = self.start;
+        let lstop = i64::min(left_cut, self.start + self.count);
+        let lcount = i64::max(0, lstop - lstart);
+
+        let cstart = i64::max(left_cut, self.start);
+        let cstop = i64::min(right_cut, self.start + self.count);
+        let ccount = i64::max(0, cstop - cstart);
+
+        let rstart = i64::max(right_cut, self.start);
+        let rstop = self.start + self.count;
+        let rcount = i64::max(0, rstop - rstart);
+
+        assert_eq!(self.count, lcount + ccount + rcount);
+        (
+            if lcount > 0 { Some(AlmanacRange { start: lstart, count: lcount }) } else { None },
+            if ccount > 0 { Some(AlmanacRange { start: cstart, count: ccount }) } else { None },
+            if rcount > 0 { Some(AlmanacRange { start: rstart, count: rcount }) } else { None },
+        )
+    }
+}
+
+fn parse_seed_ranges(value: &str) -> Option<Vec<AlmanacRange>> {
+    value.strip_prefix("seeds: ").map(|s| s
+        .split(' ')
+        .filter_map(|s| s.parse().ok())
+        .collect::<Vec<i64>>()
+        .chunks(2)
+        .map(|c| AlmanacRange { start: c[0], count: c[1] })
+        .collect())
+}
+
+#[derive(Debug)]
+struct AlmanacMapRange {
+    src_start: i64,
+    dst_start: i64,
+    count: i64
+}
+
+#[derive(Debug)]
+struct AlmanacMap {
+    ranges: Vec<AlmanacMapRange>,
+}
+
+impl AlmanacMap {
+    fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    fn add_range(&mut self, offset: AlmanacMapRange) {
+        let _ = &self.ranges.push(offset);
+    }
+
+    fn apply(&self, src_ranges: Vec<AlmanacRange>) -> Vec<AlmanacRange> {
+        let mut dst_ranges: Vec<AlmanacRange> = vec![];
+        let mut src_ranges = VecDeque::from(src_ranges);
+
+        for mr in &self.ranges {
+            let (lcut, rcut) = (mr.src_start, mr.src_start + mr.count);
+
+            let mut unmapped: Vec<AlmanacRange> = vec![];
+            while let Some(sr) = src_ranges.pop_front() {
+                // Use map range boundaries to cut the source range:
+                // the center part does overlap with it, so must be
+                // mapped, while left and right parts won't change.
+                let (left, center, right) = sr.split(lcut, rcut);
+
+                if let Some(r) = center {
+                    let mapped = AlmanacRange {
+                        start: r.start + mr.dst_start - mr.src_start,
+                        count: r.count
+                    };
+                    dst_ranges.push(mapped);
+                }
+
+                if let Some(r) = left {
+                    unmapped.push(r);
+                }
+
+                if let Some(r) = right {
+                    unmapped.push(r);
+                }
+            }
+
+            // Source ranges (or their parts) which could not be mapped
+            // through the current map range may still be successfully
+            // mapped with the next one, so put them back into the queue.
+            src_ranges.extend(unmapped);
+        }
+
+        // The ranges (or their parts) still in the queue, could not
+        // be mapped by any map range, and will remain unchanged.
+        dst_ranges.extend(src_ranges);
+
+        dst_ranges
+    }
+}
+
+fn parse_map_categories(value: &str) -> Option<(String, String)> {
+    let lines: Vec<&str> = value.trim().split('\n').collect();
+    if lines.is_empty() { return None; };
+
+    let header = lines[0];
+    if !header.ends_with(" map:") { return None; }
+
+    let tokens: Vec<&str> = header
+        .split(' ').next().unwrap()
+        .split('-').collect();
+    if tokens.len() != 3 { return None; }
+
+    let src = tokens[0].to_owned();
+    let dst = tokens[2].to_owned();
+
+    Some((src, dst))
+}
+
+fn parse_map_ranges(value: &str) -> Option<AlmanacMap> {
+    let lines: Vec<&str> = value.trim().split('\n').collect();
+    if lines.is_empty() { return None; };
+
+    let mut map = AlmanacMap::new();
+    for line in lines.iter().skip(1) {
+        let nums: Vec<i64> = line
+            .split(' ')
+            .filter_map(|s| s.parse().ok())
+            .collect();
+        assert_eq!(nums.len(), 3, "Failed parsing map:\n\n{}\n\n", value);
+        let dst_start = nums[0];
+        let src_start = nums[1];
+        let count = nums[2];
+
+        map.add_range(AlmanacMapRange { src_start, dst_start, count });
+    }
+
+    Some(map)
+}
+
+pub struct Almanac {
+    src_dst: HashMap<String, String>,
+    src_dst_map: HashMap<(String, String), AlmanacMap>,
+}
+
+impl Almanac {
+    fn new() -> Self {
+        Almanac {
+            src_dst: HashMap::new(),
+            src_dst_map: HashMap::new(),
+        }
+    }
+
+    fn add_map(&mut self, src: String, dst: String, map: AlmanacMap) {
+        self.src_dst.insert(src.to_owned(), dst.to_owned());
+        self.src_dst_map.insert((src.to_owned(), dst.to_owned()), map);
+    }
+
+    pub fn map(&self, src: &str, dst: &str, src_range: AlmanacRange) -> Option<Vec<AlmanacRange>> {
+        let mut ids = vec![src_range.clone()];
+        let mut curr = src.to_owned();
+
+        while curr != dst {
+            let next = self.src_dst.get(&curr).unwrap().to_owned();
+
+            let src_dst = &(curr.to_owned(), next.to_owned());
+            let map = self.src_dst_map.get(src_dst).unwrap();
+
+            ids = map.apply(ids);
+            curr = next;
+        }
+        Some(ids)
+    }
+}
+
+/// Parses the seed ranges and the maps of an almanac.
+pub fn parse_almanac(content: &str) -> (Vec<AlmanacRange>, Almanac) {
+    let mut seed_ranges: Vec<AlmanacRange> = vec![];
+    let mut almanac = Almanac::new();
+
+    for (i, section) in content.split("\n\n").enumerate() {
+        if section.starts_with("seeds: ") {
+            if let Some(v) = parse_seed_ranges(section) {
+                seed_ranges = v;
+            }
+        } else if section.split('\n').next().unwrap().ends_with(" map:") {
+            let (src, dst) = parse_map_categories(section)
+                .expect("Failed to parse map categories");
+            let map = parse_map_ranges(section)
+                .expect("Failed to parse map ranges");
+            almanac.add_map(src, dst, map);
+        } else {
+            panic!("Failed to parse section {i}");
+        }
+    }
+
+    (seed_ranges, almanac)
+}
+
+pub fn lowest_location(almanac: &Almanac, seed_ranges: &[AlmanacRange]) -> Option<i64> {
+    seed_ranges.iter()
+        .filter_map(|s| almanac.map("seed", "location", s.clone()))
+        .flatten()
+        .map(|r| r.start)
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn example_lowest_location() {
+        let (seed_ranges, almanac) = parse_almanac(EXAMPLE);
+        assert_eq!(lowest_location(&almanac, &seed_ranges), Some(46));
+    }
+
+    #[test]
+    fn example_single_seeds() {
+        // seeds taken one by one, as in the first part of the puzzle
+        let (_, almanac) = parse_almanac(EXAMPLE);
+        let locations: Vec<i64> = [79, 14, 55, 13].iter()
+            .map(|s| lowest_location(&almanac, &[AlmanacRange { start: *s, count: 1 }]).unwrap())
+            .collect();
+        assert_eq!(locations, [82, 43, 86, 35]);
+    }
+
+    #[test]
+    fn split_range() {
+        let (l, c, r) = AlmanacRange { start: 10, count: 10 }.split(12, 15);
+        let bounds = |r: Option<AlmanacRange>| r.map(|r| (r.start, r.count));
+        assert_eq!((bounds(l), bounds(c), bounds(r)), (Some((10, 2)), Some((12, 3)), Some((15, 5))));
+
+        let (l, c, r) = AlmanacRange { start: 10, count: 10 }.split(0, 5);
+        assert_eq!((bounds(l), bounds(c), bounds(r)), (None, None, Some((10, 10))));
+    }
+}
@@ -0,0 +1,20 @@
+//! Parse and solve phases on the committed input, run separately.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_06::{parse_races, winning_product};
+use std::hint::black_box;
+
+fn phases(c: &mut Criterion) {
+    let input = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/input")).unwrap();
+    let mut group = c.benchmark_group("day-06");
+
+    group.bench_function("parse", |b| b.iter(|| parse_races(black_box(&input))));
+
+    let races = parse_races(&input);
+    group.bench_function("solve", |b| b.iter(|| winning_product(black_box(&races))));
+
+    group.finish();
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+use std::iter::zip;
+
+/// Ways to hold the button long enough to beat the best distance.
+pub fn winning_times(race_time: i64, best_dis: i64) -> i64 {
+    let race_time = race_time as f64;
+    let best_dis = best_dis as f64;
+
+    let delta = f64::sqrt(race_time * race_time - 4.0 * best_dis);
+    let min = 0.5 * (race_time - delta);
+    let max = 0.5 * (race_time + delta);
+
+    let mut count = max.floor() - min.ceil() + 1.0;
+    if min == min.trunc() { count -= 1.0; }
+    if max == max.trunc() { count -= 1.0; }
+
+    count as i64
+}
+
+/// Race times and best distances, reading each line as a single number
+/// once the spaces are removed.
+pub fn parse_races(content: &str) -> Vec<(i64, i64)> {
+    let lines: Vec<&str> = content.split('\n').collect();
+
+    let times: Vec<i64> = lines[0]
+        .split(':').nth(1).unwrap()
+        .replace(' ', "")
+        .split(' ').filter_map(|s| s.parse().ok())
+        .collect();
+
+    let spaces: Vec<i64> = lines[1]
+        .split(':').nth(1).unwrap()
+        .replace(' ', "")
+        .split(' ').filter_map(|s| s.parse().ok())
+        .collect();
+
+    zip(times, spaces).collect()
+}
+
+/// Product of the ways to win each race.
+pub fn winning_product(races: &[(i64, i64)]) -> i64 {
+    races.iter()
+        .map(|(t, s)| winning_times(*t, *s))
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_races() {
+        let times: Vec<i64> = [(7, 9), (15, 40), (30, 200)].iter()
+            .map(|(t, s)| winning_times(*t, *s))
+            .collect();
+        assert_eq!(times, [4, 8, 9]);
+        assert_eq!(winning_product(&[(7, 9), (15, 40), (30, 200)]), 288);
+    }
+
+    #[test]
+    fn example_single_race() {
+        let races = parse_races("Time:      7  15   30\nDistance:  9  40  200\n");
+        assert_eq!(races, [(71530, 940200)]);
+        assert_eq!(winning_times(71530, 940200), 71503);
+    }
+}
@@ -1,32 +1,38 @@
+use common::{Format, Report, Timer};
+use day_06::{parse_races, winning_product};
 use std::env;
 use std::fs::File;
 use std::io::prelude::*;
-use std::iter::zip;
-
-fn winning_times(race_time: i64, best_dis: i64) -> i64 {
-    let race_time = race_time as f64;
-    let best_dis = best_dis as f64;
-
-    let delta = f64::sqrt(race_time * race_time - 4.0 * best_dis);
-    let min = 0.5 * (race_time - delta);
-    let max = 0.5 * (race_time + delta);
-
-    let mut count = max.floor() - min.ceil() + 1.0;
-    if min == min.trunc() { count -= 1.0; }
-    if max == max.trunc() { count -= 1.0; }
-
-    count as i64
-}
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() != 2 {
-        eprintln!("Usage: {} <input>", args[0]);
-        return;
+    let usage = format!("Usage: {} [--format <text|json>] [--time] <input>", args[0]);
+    let mut format = Format::Text;
+    let mut time = false;
+    let mut input: Option<&String> = None;
+
+    let mut it = args.iter().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--format" => match it.next().and_then(|v| Format::parse(v)) {
+                Some(f) => format = f,
+                None => { eprintln!("{}", usage); return },
+            },
+            "--time" => time = true,
+            _ if input.is_none() => input = Some(arg),
+            _ => { eprintln!("{}", usage); return },
+        }
     }
 
-    let mut file = match File::open(&args[1]) {
+    let input = match input {
+        Some(i) => i,
+        None => { eprintln!("{}", usage); return },
+    };
+
+    let mut timer = Timer::start();
+
+    let mut file = match File::open(input) {
         Ok(r) => r,
         Err(e) => { eprintln!("{}", e); return; },
     };
@@ -37,22 +43,21 @@ fn main() {
         Err(e) => { eprintln!("{}", e); return; },
     };
 
-    let lines: Vec<&str> = content.split('\n').collect();
+    timer.lap("read");
 
-    let times: Vec<i64> = lines[0]
-        .split(":").nth(1).unwrap()
-        .replace(" ", "")
-        .split(" ").filter_map(|s| s.parse().ok())
-        .collect();
+    let races = parse_races(&content);
+    timer.lap("parse");
 
-    let spaces: Vec<i64> = lines[1]
-        .split(":").nth(1).unwrap()
-        .replace(" ", "")
-        .split(" ").filter_map(|s| s.parse().ok())
-        .collect();
+    let prod = winning_product(&races);
+    timer.lap("solve");
 
-    let prod: i64 = zip(&times, &spaces)
-        .map(|(t, s)| winning_times(*t, *s))
-        .product();
-    println!("prod: {prod}");
+    match format {
+        Format::Text => println!("prod: {prod}"),
+        // the spaces are only removed, making a single race, in the second part
+        Format::Json => println!("{}", Report::new(6, timer.elapsed()).part2(prod)),
+    }
+
+    if time {
+        eprint!("{}", timer);
+    }
 }
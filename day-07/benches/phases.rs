@@ -0,0 +1,25 @@
+//! Parse and solve phases on the committed input, run separately.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use day_07::{parse_hands, winnings, Deck};
+use std::hint::black_box;
+
+fn phases(c: &mut Criterion) {
+    let input = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/input")).unwrap();
+    let deck = Deck::new("J23456789TQKA", "J").unwrap();
+    let mut group = c.benchmark_group("day-07");
+
+    group.bench_function("parse", |b| b.iter(|| parse_hands(black_box(&input), &deck).unwrap()));
+
+    let hands = parse_hands(&input, &deck).unwrap();
+    group.bench_function("solve", |b| b.iter_batched_ref(
+        || hands.clone(),
+        |hands| { hands.sort(); winnings(hands) },
+        BatchSize::SmallInput,
+    ));
+
+    group.finish();
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
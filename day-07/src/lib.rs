@@ -0,0 +1,327 @@
+use counter::Counter;
+use core::cmp::Ordering;
+use std::iter::zip;
+
+/// Hand type, derived from the card count signature: the number of
+/// copies of each distinct card, sorted in descending order.
+///
+/// Signatures compare lexicographically, which yields the usual ranking
+/// for five-card hands and extends naturally to any hand size.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct HandType {
+    signature: Vec<usize>,
+}
+
+impl HandType {
+    fn name(&self) -> String {
+        let sig = &self.signature;
+        let rest_single = sig.iter().skip(1).all(|n| *n == 1);
+        match sig.as_slice() {
+            [] => "empty".to_owned(),
+            [1, ..] => "high card".to_owned(),
+            [3, 2] => "full house".to_owned(),
+            [2, 2, ..] if sig.iter().skip(2).all(|n| *n == 1) =>
+                "two pairs".to_owned(),
+            [2, ..] if rest_single => "one pair".to_owned(),
+            [3, ..] if rest_single => "three of a kind".to_owned(),
+            [4, ..] if rest_single => "four of a kind".to_owned(),
+            [5, ..] if rest_single => "five of a kind".to_owned(),
+            [n, ..] if rest_single => format!("{n} of a kind"),
+            _ => sig.iter().map(|n| n.to_string())
+                .collect::<Vec<String>>().join("+"),
+        }
+    }
+}
+
+/// Card alphabet, in ascending order of strength, and the cards
+/// acting as wildcards.
+#[derive(Debug)]
+pub struct Deck {
+    cards: Vec<char>,
+    wild: Vec<bool>,
+}
+
+impl Deck {
+    pub fn new(cards: &str, wildcards: &str) -> Result<Self, String> {
+        let cards: Vec<char> = cards.chars().collect();
+        for (i, c) in cards.iter().enumerate() {
+            if cards[..i].contains(c) {
+                return Err(format!("Card '{}' appears twice in deck", c));
+            }
+        }
+        for c in wildcards.chars() {
+            if !cards.contains(&c) {
+                return Err(format!("Wildcard '{}' is not in deck", c));
+            }
+        }
+        let wild = cards.iter().map(|c| wildcards.contains(*c)).collect();
+        Ok(Deck { cards, wild })
+    }
+
+    fn value(&self, card: char) -> Option<usize> {
+        self.cards.iter().position(|c| *c == card)
+    }
+
+    fn is_wild(&self, value: usize) -> bool {
+        self.wild[value]
+    }
+
+    fn format(&self, values: &[usize]) -> String {
+        values.iter().map(|v| self.cards[*v]).collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Hand {
+    values: Vec<usize>,
+    bid: usize,
+    kind: HandType,
+}
+
+impl Hand {
+    fn new(values: Vec<usize>, bid: usize, deck: &Deck) -> Hand {
+        let kind = HandType { signature: vec![] };
+        let mut hand = Hand { values, bid, kind };
+        hand.kind = hand.improve(deck).get_type();
+        hand
+    }
+
+    pub fn parse(line: &str, deck: &Deck) -> Option<Hand> {
+        let (cards, bid) = line.split_once(' ')?;
+        let values: Option<Vec<usize>> = cards
+            .chars().map(|c| deck.value(c)).collect();
+        Some(Hand::new(values?, bid.parse().ok()?, deck))
+    }
+
+    fn get_type(&self) -> HandType {
+        let counter: Counter<&usize, usize> = self.values.iter().collect();
+        let mut signature: Vec<usize> = counter.values().copied().collect();
+        signature.sort_by(|a, b| b.cmp(a));
+        HandType { signature }
+    }
+
+    fn improve(&self, deck: &Deck) -> Hand {
+        // no wildcards, cannot improve
+        if !self.values.iter().any(|v| deck.is_wild(*v)) {
+            return self.clone();
+        }
+
+        let not_wild_counter: Counter<&usize, usize> = self.values
+            .iter().filter(|v| !deck.is_wild(**v)).collect();
+
+        // extend the most common card, preferring the highest one; with
+        // no natural card at all, emulate the highest card in the deck
+        let joker = match not_wild_counter.iter().max_by_key(|(v, n)| (**n, **v)) {
+            Some((v, _)) => **v,
+            None => match (0..deck.cards.len()).rev().find(|v| !deck.is_wild(*v)) {
+                Some(v) => v,
+                None => return self.clone(),
+            },
+        };
+
+        Hand {
+            values: self.values
+            .iter()
+            .map(|v| if deck.is_wild(*v) { joker } else { *v })
+            .collect(),
+            bid: self.bid,
+            kind: self.kind.clone(),
+        }
+    }
+
+    /// Reference implementation of `improve`: tries every substitution
+    /// for each wildcard independently and keeps the best resulting type.
+    #[cfg(test)]
+    fn best_type(&self, deck: &Deck) -> HandType {
+        let wildcards: Vec<usize> = self.values.iter().enumerate()
+            .filter(|(_, v)| deck.is_wild(**v))
+            .map(|(i, _)| i)
+            .collect();
+        let naturals: Vec<usize> = (0..deck.cards.len())
+            .filter(|v| !deck.is_wild(*v))
+            .collect();
+
+        let mut hand = self.clone();
+        let mut best = self.get_type();
+        if naturals.is_empty() {
+            return best;
+        }
+        let combinations = naturals.len().pow(wildcards.len() as u32);
+
+        for mut n in 0..combinations {
+            for i in &wildcards {
+                hand.values[*i] = naturals[n % naturals.len()];
+                n /= naturals.len();
+            }
+            best = HandType::max(best, hand.get_type());
+        }
+
+        best
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.kind != other.kind {
+            return self.kind.cmp(&other.kind);
+        }
+        for (s, o) in zip(&self.values, &other.values) {
+            if s != o { return s.cmp(o); }
+        }
+        Ordering::Equal
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Hand { }
+
+#[derive(Debug, PartialEq)]
+pub enum ExplainFormat {
+    Text,
+    Csv,
+}
+
+/// Prints, for each hand in rank order, how it was evaluated and how
+/// much it contributed to the winnings.
+pub fn explain(hands: &[Hand], deck: &Deck, format: &ExplainFormat) -> String {
+    // ranks of the other hands comparing equal to each hand: their
+    // relative order, and thus their contribution, is arbitrary
+    let ties: Vec<Vec<usize>> = hands.iter().enumerate().map(|(i, h)| {
+        hands.iter().enumerate()
+            .filter(|(j, o)| *j != i && h.cmp(o) == Ordering::Equal)
+            .map(|(j, _)| j + 1)
+            .collect()
+    }).collect();
+
+    let rows: Vec<[String; 7]> = hands.iter().enumerate().map(|(i, h)| [
+        (i + 1).to_string(),
+        deck.format(&h.values),
+        deck.format(&h.improve(deck).values),
+        h.kind.name(),
+        h.bid.to_string(),
+        ((i + 1) * h.bid).to_string(),
+        ties[i].iter().map(|r| r.to_string()).collect::<Vec<String>>().join(" "),
+    ]).collect();
+    let header = ["rank", "cards", "improved", "type", "bid", "contribution", "ties"];
+
+    let mut out = String::new();
+    match format {
+        ExplainFormat::Csv => {
+            out.push_str(&format!("{}\n", header.join(",")));
+            for row in &rows {
+                out.push_str(&format!("{}\n", row.join(",")));
+            }
+        },
+        ExplainFormat::Text => {
+            let widths: Vec<usize> = (0..header.len()).map(|k| rows.iter()
+                .map(|r| r[k].len())
+                .chain([header[k].len()])
+                .max().unwrap()
+            ).collect();
+            let line = |cells: Vec<&str>| cells.iter().enumerate()
+                .map(|(k, c)| format!("{:<w$}", c, w = widths[k]))
+                .collect::<Vec<String>>().join("  ").trim_end().to_owned();
+
+            out.push_str(&format!("{}\n", line(header.to_vec())));
+            for row in &rows {
+                out.push_str(&format!("{}\n", line(row.iter().map(|c| c.as_str()).collect())));
+            }
+
+            let tied = ties.iter().filter(|t| !t.is_empty()).count();
+            out.push_str(&format!("{} hands, {} involved in ties\n", hands.len(), tied));
+        },
+    }
+    out
+}
+
+/// Hands on the non-empty lines, or the first line which isn't one.
+pub fn parse_hands<'a>(contents: &'a str, deck: &Deck) -> Result<Vec<Hand>, &'a str> {
+    contents.split('\n')
+        .filter(|l| !l.is_empty())
+        .map(|l| Hand::parse(l, deck).ok_or(l))
+        .collect()
+}
+
+/// Total winnings of hands sorted from the weakest, each bid multiplied
+/// by its rank.
+pub fn winnings(hands: &[Hand]) -> usize {
+    hands.iter().enumerate()
+        .map(|(i, h)| (i + 1) * h.bid)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_hands(deck: &Deck, size: usize) -> impl Iterator<Item = Hand> + '_ {
+        let n_cards = deck.cards.len();
+        (0..n_cards.pow(size as u32)).map(move |mut n| {
+            let mut values = vec![];
+            for _ in 0..size {
+                values.push(n % n_cards);
+                n /= n_cards;
+            }
+            Hand::new(values, 0, deck)
+        })
+    }
+
+    #[test]
+    fn improve_matches_best_type() {
+        let deck = Deck::new("J23456789TQKA", "J").unwrap();
+        for hand in all_hands(&deck, 5) {
+            assert_eq!(hand.kind, hand.best_type(&deck), "{:?}", hand);
+        }
+    }
+
+    #[test]
+    fn improve_matches_best_type_with_two_wild_ranks() {
+        let deck = Deck::new("WX23456", "WX").unwrap();
+        for hand in all_hands(&deck, 6) {
+            assert_eq!(hand.kind, hand.best_type(&deck), "{:?}", hand);
+        }
+    }
+
+    #[test]
+    fn type_names_from_signature() {
+        let deck = Deck::new("23456789TJQKA", "").unwrap();
+        let name = |cards: &str| Hand::parse(&format!("{cards} 0"), &deck)
+            .unwrap().kind.name();
+
+        assert_eq!(name("23456"), "high card");
+        assert_eq!(name("22456"), "one pair");
+        assert_eq!(name("22446"), "two pairs");
+        assert_eq!(name("22246"), "three of a kind");
+        assert_eq!(name("22244"), "full house");
+        assert_eq!(name("22224"), "four of a kind");
+        assert_eq!(name("22222"), "five of a kind");
+        assert_eq!(name("222222"), "6 of a kind");
+        assert_eq!(name("222333"), "3+3");
+    }
+
+    #[test]
+    fn example_winnings() {
+        let example = "32T3K 765\nT55J5 684\nKK677 28\nKTJJT 220\nQQQJA 483";
+        let total = |deck: &Deck| {
+            let mut hands = parse_hands(example, deck).unwrap();
+            hands.sort();
+            winnings(&hands)
+        };
+
+        assert_eq!(total(&Deck::new("23456789TJQKA", "").unwrap()), 6440);
+        assert_eq!(total(&Deck::new("J23456789TQKA", "J").unwrap()), 5905);
+        assert_eq!(parse_hands("32T3K 765\nT55J5\n", &Deck::new("23456789TJQKA", "").unwrap()).err(),
+                   Some("T55J5"));
+    }
+}
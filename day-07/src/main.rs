@@ -1,113 +1,64 @@
-use counter::Counter;
-use core::cmp::Ordering;
-use std::collections::HashMap;
+use common::{diag, Format, Report, Timer};
+use day_07::{explain, parse_hands, winnings, Deck, ExplainFormat};
 use std::env;
-use std::iter::zip;
 use std::fs::File;
 use std::io::prelude::*;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
-    HighCard     = 0,
-    OnePair      = 1,
-    TwoPairs     = 2,
-    ThreeOfAKind = 3,
-    FullHouse    = 4,
-    FourOfAKind  = 5,
-    FiveOfAKind  = 6,
-}
-
-#[derive(Clone, Debug)]
-struct Hand {
-    values: Vec<usize>,
-    bid: usize,
-}
-
-impl Hand {
-    fn get_type(&self) -> HandType {
-        let counter: Counter<&usize, usize> = self.values.iter().collect();
-        let n: usize = counter.len();
-        let mc: Vec<(&usize, usize)> = counter.most_common();
-
-        if n == 5                       { return HandType::HighCard; }
-        if n == 4                       { return HandType::OnePair; }
-        if mc[0].1 == 2 && mc[1].1 == 2 { return HandType::TwoPairs; }
-        if mc[0].1 == 3 && mc[1].1 == 1 { return HandType::ThreeOfAKind; }
-        if mc[0].1 == 3 && mc[1].1 == 2 { return HandType::FullHouse; }
-        if mc[0].1 == 4                 { return HandType::FourOfAKind; }
-        if n == 1                       { return HandType::FiveOfAKind; }
-
-        None::<HandType>.expect("Failed to determine hand type: {self}")
-    }
+/// Puzzle rules: the first part without jokers, the second with J wild.
+const PART1_DECK: &str = "23456789TJQKA";
+const PART2_DECK: &str = "J23456789TQKA";
 
-    fn improve(&self) -> Hand {
-        // no jokers (value 0), cannot improve
-        if !self.values.contains(&0) {
-            return self.clone();
-        }
-
-        let not_j_counter: Counter<&usize, usize> = self.values
-            .iter().filter(|v| **v != 0).collect();
-        let mc: Vec<(&usize, usize)> = not_j_counter.most_common();
-
-        let joker: usize = match self.get_type() {
-            // five jokers
-            HandType::FiveOfAKind => 12,
-            // extend the highest pair
-            HandType::TwoPairs => *mc.iter()
-                .filter(|(v, n)| **v != 0 && *n == 2)
-                .map(|(v, _)| *v).max().unwrap(),
-            // emulate highest card and become a pair
-            HandType::HighCard => *self.values.iter().max().unwrap(),
-            // extend the best part of current type
-            _ => *mc[0].0,
-        };
-
-        Hand {
-            values: self.values
-            .iter()
-            .map(|v| if *v == 0 as usize { joker } else { *v })
-            .collect(),
-            bid: self.bid.clone()
-        }
-    }
-}
+fn main() {
+    let args: Vec<String> = env::args().collect();
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.improve().get_type() != other.improve().get_type() {
-            return self.improve().get_type().cmp(&other.improve().get_type());
-        }
-        for (s, o) in zip(&self.values, &other.values) {
-            if s != o { return s.cmp(&o); }
+    let usage = format!(
+        "Usage: {} [--deck <cards>] [--wild <cards>] [--explain <text|csv>] [--format <text|json>] [--time] <input>",
+        args[0]);
+
+    // defaults to the puzzle rules, with J as the weakest card and joker
+    let mut deck_cards = PART2_DECK.to_owned();
+    let mut wildcards = "J".to_owned();
+    // the JSON report holds puzzle answers, which other rules don't give
+    let mut custom_rules = false;
+    let mut explain_format: Option<ExplainFormat> = None;
+    let mut format = Format::Text;
+    let mut time = false;
+    let mut path: Option<&String> = None;
+
+    let mut it = args.iter().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--deck" => match it.next() {
+                Some(v) => { deck_cards = v.to_owned(); custom_rules = true },
+                None => { eprintln!("{}", usage); return },
+            },
+            "--wild" => match it.next() {
+                Some(v) => { wildcards = v.to_owned(); custom_rules = true },
+                None => { eprintln!("{}", usage); return },
+            },
+            "--explain" => match it.next().map(|v| v.as_str()) {
+                Some("text") => explain_format = Some(ExplainFormat::Text),
+                Some("csv") => explain_format = Some(ExplainFormat::Csv),
+                _ => { eprintln!("{}", usage); return },
+            },
+            "--format" => match it.next().and_then(|v| Format::parse(v)) {
+                Some(f) => format = f,
+                None => { eprintln!("{}", usage); return },
+            },
+            "--time" => time = true,
+            _ if path.is_none() => path = Some(arg),
+            _ => { eprintln!("{}", usage); return },
         }
-        Ordering::Equal
-    }
-}
-
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl PartialEq for Hand {
-    fn eq(&self, other: &Self) -> bool {
-        self == other
     }
-}
 
-impl Eq for Hand { }
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
+    let path = match path {
+        Some(p) => p,
+        None => { eprintln!("{}", usage); return },
+    };
 
-    if args.len() != 2 {
-        eprintln!("Usage: {} <input>", args[0]);
-        return
-    }
+    let mut timer = Timer::start();
 
-    let mut file = match File::open(&args[1]) {
+    let mut file = match File::open(path) {
         Ok(r) => r,
         Err(e) => { eprintln!("{}", e); return },
     };
@@ -117,30 +68,47 @@ fn main() {
         Ok(r) => r,
         Err(e) => { eprintln!("{}", e); return },
     };
+    timer.lap("read");
 
-    let card_value: HashMap<char, usize> = "J23456789TQKA"
-        .chars().enumerate().map(|(i, c)| (c, i)).collect();
+    let deck = match Deck::new(&deck_cards, &wildcards) {
+        Ok(d) => d,
+        Err(e) => { eprintln!("{}", e); return },
+    };
 
-    let mut hands = Vec::<Hand>::new();
-    for line in contents.split('\n').filter(|l| l.len() > 0) {
-        let parts: Vec<&str> = line.split(' ').collect();
-        if parts.len() != 2 {
-            continue;
-        }
+    let mut hands = match parse_hands(&contents, &deck) {
+        Ok(h) => h,
+        Err(line) => { eprintln!("Failed to parse hand: {}", line); return },
+    };
 
-        let values: Vec<usize> = parts[0]
-            .chars().map(|c| card_value[&c]).collect();
+    timer.lap("parse");
 
-        let bid: usize = parts[1].parse().unwrap();
+    hands.sort();
+    let sum = winnings(&hands);
+    let part1 = match (format, custom_rules) {
+        (Format::Json, false) => Deck::new(PART1_DECK, "").ok()
+            .and_then(|deck| parse_hands(&contents, &deck).ok())
+            .map(|mut hands| { hands.sort(); winnings(&hands) }),
+        _ => None,
+    };
+    timer.lap("solve");
 
-        let hand = Hand { values, bid };
-        hands.push(hand);
+    if let Some(explain_format) = &explain_format {
+        diag!(format, "{}", explain(&hands, &deck, explain_format));
     }
 
-    hands.sort();
+    match format {
+        // stdout then holds the CSV alone, ready to be redirected
+        Format::Text if explain_format == Some(ExplainFormat::Csv) => eprintln!("winnings: {}", sum),
+        Format::Text => println!("winnings: {}", sum),
+        Format::Json => {
+            let mut report = Report::new(7, timer.elapsed());
+            if let Some(part1) = part1 { report = report.part1(part1) }
+            if !custom_rules { report = report.part2(sum) }
+            println!("{}", report);
+        },
+    }
 
-    let sum: usize = hands.iter().enumerate()
-        .map(|(i, h)| (i + 1) * h.bid)
-        .sum();
-    println!("winnings: {}", sum);
+    if time {
+        eprint!("{}", timer);
+    }
 }
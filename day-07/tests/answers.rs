@@ -0,0 +1,37 @@
+//! Runs the binary on the committed input and compares what it prints
+//! with the answers recorded in `answers.toml`.
+
+#[test]
+fn input_answers() {
+    common::answers::check(env!("CARGO_BIN_EXE_day-07"), env!("CARGO_MANIFEST_DIR"));
+}
+
+#[test]
+fn json_alone_on_stdout() {
+    common::answers::check_json(env!("CARGO_BIN_EXE_day-07"), env!("CARGO_MANIFEST_DIR"),
+                                &["--explain", "text", "--time"]);
+}
+
+#[test]
+fn csv_explanation_keeps_answers() {
+    common::answers::check_json(env!("CARGO_BIN_EXE_day-07"), env!("CARGO_MANIFEST_DIR"),
+                                &["--explain", "csv", "--time"]);
+}
+
+#[test]
+fn csv_explanation_alone_on_stdout() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_day-07"))
+        .args(["--explain", "csv"])
+        .arg(dir.join("input"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let csv = String::from_utf8(output.stdout).unwrap();
+    let hands = std::fs::read_to_string(dir.join("input")).unwrap().lines().count();
+    assert_eq!(csv.lines().next(), Some("rank,cards,improved,type,bid,contribution,ties"));
+    assert_eq!(csv.lines().count(), hands + 1);
+    assert!(csv.lines().all(|l| l.split(',').count() == 7));
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with("winnings: "));
+}
@@ -0,0 +1,62 @@
+//! The interned walk against the former string-keyed one, on a generated
+//! network: a chain of nodes where L moves forward and R stays in place,
+//! walked with pseudo-random instructions.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_08::{Direction, Graph};
+use std::collections::HashMap;
+use std::hint::black_box;
+
+const SIZE: usize = 1_000_000;
+
+fn name(i: usize) -> String {
+    if i == 0 { "AAA".to_owned() }
+    else if i == SIZE - 1 { "ZZZ".to_owned() }
+    else { format!("N{:06}", i) }
+}
+
+fn network(c: &mut Criterion) {
+    let mut seed: u64 = 42;
+    let instructions: String = (0..997).map(|_| {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        if seed >> 63 == 0 { 'L' } else { 'R' }
+    }).collect();
+    let network: Vec<String> = (0..SIZE).map(|i| format!("{} = ({}, {})",
+        name(i), name(usize::min(i + 1, SIZE - 1)), name(i))).collect();
+    let lines: Vec<&str> = network.iter().map(|l| l.as_str()).collect();
+
+    let direction = Direction::new(instructions.chars().collect()).unwrap();
+    let graph = Graph::new(&lines);
+    let (start, end) = (graph.id("AAA").unwrap(), graph.id("ZZZ").unwrap());
+
+    // successors by (node, turn) name, as before interning
+    let mut nodes = HashMap::<(&str, char), &str>::new();
+    for line in &lines {
+        let (src, succ) = line.split_once(" = (").unwrap();
+        let (left, right) = succ.trim_end_matches(')').split_once(", ").unwrap();
+        nodes.insert((src, 'L'), left);
+        nodes.insert((src, 'R'), right);
+    }
+
+    let mut group = c.benchmark_group("day-08 network");
+    group.sample_size(10);
+
+    group.bench_function("strings", |b| b.iter(|| {
+        let mut curr = "AAA";
+        let mut steps = 0;
+        for turn in black_box(&instructions).chars().cycle() {
+            curr = nodes[&(curr, turn)];
+            steps += 1;
+            if curr == "ZZZ" { break }
+        }
+        steps
+    }));
+    group.bench_function("interned", |b| b.iter(|| {
+        graph.path(black_box(start), end, &direction).unwrap().len()
+    }));
+
+    group.finish();
+}
+
+criterion_group!(benches, network);
+criterion_main!(benches);
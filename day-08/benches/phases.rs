@@ -0,0 +1,26 @@
+//! Parse and solve phases on the committed input, run separately.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_08::{Direction, Graph};
+use std::hint::black_box;
+
+fn phases(c: &mut Criterion) {
+    let input = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/input")).unwrap();
+    let lines: Vec<&str> = input.lines().filter(|l| !l.is_empty()).collect();
+    let direction = Direction::new(lines[0].chars().collect()).unwrap();
+    let mut group = c.benchmark_group("day-08");
+
+    group.bench_function("parse", |b| b.iter(|| Graph::new(black_box(&lines[1..]))));
+
+    let graph = Graph::new(&lines[1..]);
+    let (start, end) = (graph.id("AAA").unwrap(), graph.id("ZZZ").unwrap());
+    group.bench_function("path", |b| b.iter(|| {
+        graph.path(black_box(start), end, &direction).unwrap().len()
+    }));
+    group.bench_function("ghosts", |b| b.iter(|| black_box(&graph).ghost_walk(&direction).unwrap().steps));
+
+    group.finish();
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
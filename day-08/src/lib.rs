@@ -0,0 +1,766 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::fmt;
+
+#[derive(Debug, Hash, Eq, PartialEq)]
+enum Turn {
+    Left,
+    Right,
+}
+
+#[derive(Debug)]
+pub struct Direction<'a> {
+    turns: Vec<&'a Turn>,
+}
+
+impl Direction<'_> {
+    /// Parses the instructions, which walks repeat: there must be at least one.
+    pub fn new(chars: Vec<char>) -> Result<Self, String> {
+        let turns: Vec<&Turn> = chars.iter().map(|c| match c {
+            'L' => Ok(&Turn::Left),
+            'R' => Ok(&Turn::Right),
+            _ => Err(format!("Failed to parse turn from char {:?}", c)),
+        }).collect::<Result<_, _>>()?;
+
+        if turns.is_empty() {
+            return Err("No instructions".to_owned());
+        }
+        Ok(Self { turns })
+    }
+}
+
+/// Periodic behaviour of a walk, detected on (node, instruction index)
+/// states: after `entry` steps the walk repeats every `length` steps.
+#[derive(Debug)]
+pub struct Cycle {
+    pub entry: usize,
+    pub length: usize,
+    /// Steps, before the first repetition, landing on an end node.
+    pub hits: Vec<usize>,
+}
+
+impl Cycle {
+    fn is_hit(&self, step: u64) -> bool {
+        let (entry, length) = (self.entry as u64, self.length as u64);
+        let step = if step < entry { step } else { entry + (step - entry) % length };
+        self.hits.binary_search(&(step as usize)).is_ok()
+    }
+
+    /// Residues, modulo the cycle length, of the hits inside the cycle.
+    fn residues(&self) -> Vec<u64> {
+        self.hits.iter()
+            .filter(|h| **h >= self.entry)
+            .map(|h| (*h % self.length) as u64)
+            .collect()
+    }
+
+    /// Whether end nodes are reached exactly every `length` steps, the
+    /// assumption making the plain LCM of cycle lengths the answer.
+    pub fn is_simple(&self) -> bool {
+        self.hits == [self.length]
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GhostMethod {
+    Lcm,
+    Crt,
+    Simulation,
+}
+
+#[derive(Debug)]
+pub struct GhostWalk {
+    pub starts: Vec<NodeId>,
+    pub cycles: Vec<Cycle>,
+    pub method: GhostMethod,
+    pub steps: Option<u64>,
+}
+
+/// Upper bound on the distinct solutions kept by the CRT solver while
+/// merging cycles, before falling back to a simulation.
+const MAX_CRT_SOLUTIONS: usize = 1 << 16;
+
+/// Steps the simulation walks before giving up.
+const MAX_SIMULATION_STEPS: u64 = 1 << 27;
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Least common multiple, if it fits in a `u64`.
+fn lcm(a: u64, b: u64) -> Option<u64> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Merges `x = a1 (mod m1)` and `x = a2 (mod m2)` into a single
+/// congruence, if the two are compatible. Fails if the merged modulus
+/// doesn't fit in a `u64`.
+fn crt<'a>(a1: u64, m1: u64, a2: u64, m2: u64) -> Result<Option<(u64, u64)>, PathError<'a>> {
+    let g = gcd(m1, m2);
+    let modulus = lcm(m1, m2).ok_or(PathError::Overflow)?;
+    let (a1, m1, a2, m2) = (a1 as i128, m1 as i128, a2 as i128, m2 as i128);
+    let diff = a2 - a1;
+    if diff % g as i128 != 0 { return Ok(None); }
+
+    // extended Euclid for the inverse of m1/g modulo m2/g; as the
+    // modulus fits in a u64, the products below fit in an i128
+    let (m, n) = (m1 / g as i128, m2 / g as i128);
+    let (mut r0, mut r1, mut s0, mut s1) = (m.rem_euclid(n), n, 1_i128, 0_i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+    }
+    let k = ((diff / g as i128).rem_euclid(n) * s0).rem_euclid(n);
+
+    Ok(Some(((a1 + m1 * k).rem_euclid(modulus as i128) as u64, modulus)))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PathError<'a> {
+    /// The walk entered a cycle, at `node` after `entry` steps, which
+    /// repeats every `length` steps without ever visiting `end`.
+    Unreachable { end: &'a str, node: &'a str, entry: usize, length: usize },
+    /// The walk reached a node referenced as a successor but never defined.
+    Undefined(&'a str),
+    /// The steps to reach the end don't fit in a `u64`.
+    Overflow,
+    /// The walk was stopped after `steps` steps without an answer.
+    GaveUp { steps: u64 },
+}
+
+impl fmt::Display for PathError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathError::Unreachable { end, node, entry, length } => write!(f,
+                "{} is unreachable: walk cycles from {} after {} steps, every {} steps",
+                end, node, entry, length),
+            PathError::Undefined(node) => write!(f, "walk reached undefined node {}", node),
+            PathError::Overflow => write!(f, "steps overflow a 64-bit integer"),
+            PathError::GaveUp { steps } => write!(f, "gave up after {} steps", steps),
+        }
+    }
+}
+
+/// Node id, as assigned by `Graph::intern`.
+pub type NodeId = u32;
+
+/// Successor of nodes referenced but never defined.
+const UNDEFINED: NodeId = NodeId::MAX;
+
+/// Colours assigned, in turn, to the walks highlighted in DOT exports.
+const DOT_COLOURS: [&str; 8] = [
+    "red", "blue", "darkgreen", "orange", "purple", "brown", "magenta", "cyan",
+];
+
+#[derive(Debug)]
+pub struct Graph<'a> {
+    pub names: Vec<&'a str>,
+    ids: HashMap<&'a str, NodeId>,
+    left: Vec<NodeId>,
+    right: Vec<NodeId>,
+}
+
+impl<'a> Graph<'a> {
+    pub fn new(lines: &'a [&str]) -> Graph<'a> {
+        let re = Regex::new(r"^(?<src>\w*) = \((?<left>\w*), (?<right>\w*)\)$").unwrap();
+
+        let mut graph = Graph { names: vec![], ids: HashMap::new(), left: vec![], right: vec![] };
+        for line in lines {
+            if let Some(c) = re.captures(line) {
+                let src = graph.intern(c.name("src").unwrap().as_str());
+                let left = graph.intern(c.name("left").unwrap().as_str());
+                let right = graph.intern(c.name("right").unwrap().as_str());
+                graph.left[src as usize] = left;
+                graph.right[src as usize] = right;
+            }
+        }
+
+        graph
+    }
+
+    fn intern(&mut self, name: &'a str) -> NodeId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = self.names.len() as NodeId;
+        self.names.push(name);
+        self.ids.insert(name, id);
+        self.left.push(UNDEFINED);
+        self.right.push(UNDEFINED);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &'a str {
+        self.names[id as usize]
+    }
+
+    fn next(&self, curr: NodeId, turn: &Turn) -> Result<NodeId, PathError<'a>> {
+        let next = match turn {
+            Turn::Left => self.left[curr as usize],
+            Turn::Right => self.right[curr as usize],
+        };
+        if next == UNDEFINED {
+            return Err(PathError::Undefined(self.name(curr)));
+        }
+        Ok(next)
+    }
+
+    pub fn is_defined(&self, id: NodeId) -> bool {
+        self.left[id as usize] != UNDEFINED
+    }
+
+    /// Nodes referenced as successors, but without a definition.
+    pub fn undefined(&self) -> Vec<NodeId> {
+        (0..self.names.len() as NodeId).filter(|id| !self.is_defined(*id)).collect()
+    }
+
+    /// Nodes whose edges both point back to themselves: once there, a
+    /// walk never leaves.
+    pub fn self_loops(&self) -> Vec<NodeId> {
+        (0..self.names.len() as NodeId)
+            .filter(|id| self.left[*id as usize] == *id && self.right[*id as usize] == *id)
+            .collect()
+    }
+
+    /// Nodes reachable from `start`, choosing each turn freely.
+    pub fn reachable(&self, start: NodeId) -> Vec<bool> {
+        let mut visited = vec![false; self.names.len()];
+        let mut stack = vec![start];
+        visited[start as usize] = true;
+        while let Some(curr) = stack.pop() {
+            if !self.is_defined(curr) { continue }
+            for next in [self.left[curr as usize], self.right[curr as usize]] {
+                if !visited[next as usize] {
+                    visited[next as usize] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        visited
+    }
+
+    /// Nodes reachable from `start` following the given instructions,
+    /// up to the first repeated state or undefined node.
+    pub fn forced_reachable(&self, start: NodeId, direction: &Direction) -> Vec<bool> {
+        let n = direction.turns.len();
+        let mut visited = vec![false; self.names.len()];
+        let mut seen = vec![false; self.names.len()];
+        let mut curr = start;
+        visited[start as usize] = true;
+
+        for (step, turn) in direction.turns.iter().cycle().enumerate() {
+            if step % n == 0 {
+                if seen[curr as usize] { break }
+                seen[curr as usize] = true;
+            }
+            let Ok(next) = self.next(curr, turn) else { break };
+            curr = next;
+            visited[curr as usize] = true;
+        }
+        visited
+    }
+
+    /// Minimum number of steps from `start` to `end` when each turn can
+    /// be chosen freely, along with the instructions achieving it.
+    pub fn shortest_route(&self, start: NodeId, end: NodeId) -> Option<(usize, String)> {
+        // BFS, remembering for each node its predecessor and the turn taken
+        let mut prev: Vec<Option<(NodeId, char)>> = vec![None; self.names.len()];
+        let mut queue = VecDeque::from([start]);
+        let mut visited = vec![false; self.names.len()];
+        visited[start as usize] = true;
+
+        while let Some(curr) = queue.pop_front() {
+            if curr == end { break }
+            if !self.is_defined(curr) { continue }
+            for (next, turn) in [(self.left[curr as usize], 'L'), (self.right[curr as usize], 'R')] {
+                if !visited[next as usize] {
+                    visited[next as usize] = true;
+                    prev[next as usize] = Some((curr, turn));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if !visited[end as usize] {
+            return None;
+        }
+
+        let mut turns = vec![];
+        let mut curr = end;
+        while let Some((p, turn)) = prev[curr as usize] {
+            turns.push(turn);
+            curr = p;
+        }
+        turns.reverse();
+        Some((turns.len(), turns.into_iter().collect()))
+    }
+
+    pub fn starts(&self, suffix: char) -> Vec<NodeId> {
+        let mut starts: Vec<NodeId> = (0..self.names.len() as NodeId)
+            .filter(|id| self.is_defined(*id) && self.name(*id).ends_with(suffix))
+            .collect();
+        starts.sort_by_key(|id| self.name(*id));
+        starts
+    }
+
+    /// Finds where the walk from `start` becomes periodic, returning the
+    /// first node of the cycle, the steps to reach it and the cycle length.
+    pub fn find_cycle(&self, start: NodeId, direction: &Direction)
+            -> Result<(NodeId, usize, usize), PathError<'a>> {
+        let turns = &direction.turns;
+        let n = turns.len();
+
+        // a periodic walk goes through the first instruction in every
+        // period, so remembering the states there is enough
+        let mut seen = vec![usize::MAX; self.names.len()];
+        let mut curr = start;
+        let mut step = 0;
+        let length = loop {
+            if step % n == 0 {
+                let first = seen[curr as usize];
+                if first != usize::MAX { break step - first; }
+                seen[curr as usize] = step;
+            }
+            curr = self.next(curr, turns[step % n])?;
+            step += 1;
+        };
+
+        // the length is a multiple of the instruction count, so a walk
+        // `length` steps ahead meets this one right at the cycle entry
+        let mut lead = start;
+        for step in 0..length {
+            lead = self.next(lead, turns[step % n])?;
+        }
+        let mut trail = start;
+        let mut entry = 0;
+        while lead != trail {
+            lead = self.next(lead, turns[entry % n])?;
+            trail = self.next(trail, turns[entry % n])?;
+            entry += 1;
+        }
+
+        Ok((trail, entry, length))
+    }
+
+    fn cycle(&self, start: NodeId, direction: &Direction, ends: &[bool])
+            -> Result<Cycle, PathError<'a>> {
+        let (_, entry, length) = self.find_cycle(start, direction)?;
+
+        let mut hits = vec![];
+        let mut curr = start;
+        for step in 0..entry + length {
+            if ends[curr as usize] { hits.push(step) }
+            curr = self.next(curr, direction.turns[step % direction.turns.len()])?;
+        }
+
+        Ok(Cycle { entry, length, hits })
+    }
+
+    /// Walks from every node ending in `A` at the same time, until all
+    /// walks are on nodes ending in `Z`.
+    pub fn ghost_walk(&self, direction: &Direction) -> Result<GhostWalk, PathError<'a>> {
+        let ends: Vec<bool> = self.names.iter().map(|n| n.ends_with('Z')).collect();
+        let starts = self.starts('A');
+        let cycles = starts.iter()
+            .map(|s| self.cycle(*s, direction, &ends))
+            .collect::<Result<Vec<Cycle>, _>>()?;
+
+        if cycles.is_empty() {
+            return Ok(GhostWalk { starts, cycles, method: GhostMethod::Lcm, steps: None });
+        }
+
+        if cycles.iter().all(|c| c.is_simple()) {
+            let steps = cycles.iter().map(|c| c.length as u64).try_fold(1, lcm)
+                .ok_or(PathError::Overflow)?;
+            return Ok(GhostWalk { starts, cycles, method: GhostMethod::Lcm, steps: Some(steps) });
+        }
+
+        let Some((solutions, modulus)) = Self::crt_solutions(&cycles)? else {
+            let steps = self.ghost_simulation(direction, &starts, &cycles, &ends, MAX_SIMULATION_STEPS)?;
+            return Ok(GhostWalk { starts, cycles, method: GhostMethod::Simulation, steps });
+        };
+
+        let steps = Self::ghost_crt(&cycles, &solutions, modulus)?;
+        Ok(GhostWalk { starts, cycles, method: GhostMethod::Crt, steps })
+    }
+
+    /// Merges the residues of every cycle, one cycle at a time, into the
+    /// residues modulo the LCM of all lengths where every walk is on an end
+    /// node. `None` if more than `MAX_CRT_SOLUTIONS` survive a merge.
+    fn crt_solutions(cycles: &[Cycle]) -> Result<Option<(Vec<u64>, u64)>, PathError<'a>> {
+        // the most constraining cycles first keep the solutions few
+        let mut order: Vec<(&Cycle, Vec<u64>)> = cycles.iter().map(|c| (c, c.residues())).collect();
+        order.sort_by_key(|(_, residues)| residues.len());
+
+        let (mut solutions, mut modulus) = (vec![0], 1);
+        for (c, residues) in order {
+            let mut merged = vec![];
+            for a in &solutions {
+                for r in &residues {
+                    merged.extend(crt(*a, modulus, *r, c.length as u64)?.map(|(a, _)| a));
+                }
+            }
+            modulus = lcm(modulus, c.length as u64).ok_or(PathError::Overflow)?;
+            // merges sharing a modulus collapse onto the same residues
+            merged.sort_unstable();
+            merged.dedup();
+            if merged.len() > MAX_CRT_SOLUTIONS {
+                return Ok(None);
+            }
+            if merged.is_empty() {
+                return Ok(Some((merged, modulus)));
+            }
+            solutions = merged;
+        }
+        Ok(Some((solutions, modulus)))
+    }
+
+    fn ghost_crt(cycles: &[Cycle], solutions: &[u64], modulus: u64) -> Result<Option<u64>, PathError<'a>> {
+        // before every walk entered its cycle, just check each step
+        let prefix = cycles.iter().map(|c| c.entry as u64).max().unwrap_or(0);
+        if let Some(step) = (1..prefix).find(|t| cycles.iter().all(|c| c.is_hit(*t))) {
+            return Ok(Some(step));
+        }
+
+        // afterwards, the first step past the prefix of each solution, if it fits
+        let m = modulus;
+        let steps: Vec<Option<u64>> = solutions.iter().map(|a| {
+            let step = match *a >= prefix {
+                true => *a,
+                false => (prefix - a).div_ceil(m).checked_mul(m)?.checked_add(*a)?,
+            };
+            Some(if step == 0 { m } else { step })
+        }).collect();
+        match steps.iter().flatten().min() {
+            Some(step) => Ok(Some(*step)),
+            None if steps.is_empty() => Ok(None),
+            None => Err(PathError::Overflow),
+        }
+    }
+
+    /// Walks all ghosts together for at most `budget` steps, giving up
+    /// if that's not enough to either meet or prove they never do.
+    fn ghost_simulation(&self, direction: &Direction, starts: &[NodeId], cycles: &[Cycle],
+                        ends: &[bool], budget: u64) -> Result<Option<u64>, PathError<'a>> {
+        // once all walks are periodic, states repeat with the LCM period
+        let prefix = cycles.iter().map(|c| c.entry as u64).max().unwrap_or(0);
+        let bound = cycles.iter().map(|c| c.length as u64).try_fold(1, lcm)
+            .and_then(|period| period.checked_add(prefix))
+            .filter(|bound| *bound <= budget);
+
+        let mut currs = starts.to_vec();
+        let turns = direction.turns.iter().cycle();
+        for (step, turn) in (1..=bound.unwrap_or(budget)).zip(turns) {
+            for curr in currs.iter_mut() {
+                *curr = self.next(*curr, turn)?;
+            }
+            if currs.iter().all(|c| ends[*c as usize]) {
+                return Ok(Some(step));
+            }
+        }
+        match bound {
+            Some(_) => Ok(None),
+            None => Err(PathError::GaveUp { steps: budget }),
+        }
+    }
+
+    /// Renders the network in Graphviz DOT format, highlighting the edges
+    /// taken by each walk, given as its start node and number of steps;
+    /// a walk reaching an undefined node is highlighted up to there.
+    pub fn to_dot(&self, direction: &Direction, walks: &[(NodeId, usize)], ends: &[bool]) -> String {
+        let n = direction.turns.len();
+        let mut walked = HashMap::<(NodeId, &Turn), Vec<&str>>::new();
+        for (k, (start, steps)) in walks.iter().enumerate() {
+            let colour = DOT_COLOURS[k % DOT_COLOURS.len()];
+            let mut curr = *start;
+            for step in 0..*steps {
+                let turn = direction.turns[step % n];
+                let colours = walked.entry((curr, turn)).or_default();
+                if !colours.contains(&colour) { colours.push(colour) }
+                let Ok(next) = self.next(curr, turn) else { break };
+                curr = next;
+            }
+        }
+
+        let mut dot = String::from("digraph network {\n");
+        for id in 0..self.names.len() as NodeId {
+            let shape = if walks.iter().any(|(s, _)| *s == id) { "box" }
+                else if ends[id as usize] { "doublecircle" }
+                else { "ellipse" };
+            dot.push_str(&format!("    \"{}\" [shape={}];\n", self.name(id), shape));
+        }
+        for id in 0..self.names.len() as NodeId {
+            let edges = [
+                (&Turn::Left, self.left[id as usize], 'L'),
+                (&Turn::Right, self.right[id as usize], 'R'),
+            ];
+            for (turn, succ, label) in edges {
+                if succ == UNDEFINED { continue }
+                let style = match walked.get(&(id, turn)) {
+                    Some(colours) => format!(", color=\"{}\", penwidth=2", colours.join(":")),
+                    None => String::new(),
+                };
+                dot.push_str(&format!("    \"{}\" -> \"{}\" [label={}{}];\n",
+                                      self.name(id), self.name(succ), label, style));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn path(&self, start: NodeId, end: NodeId, direction: &Direction)
+            -> Result<Vec<NodeId>, PathError<'a>> {
+        let n = direction.turns.len();
+        let mut seen = vec![false; self.names.len()];
+        let mut curr = start;
+        let mut path = vec![];
+
+        for (step, turn) in direction.turns.iter().cycle().enumerate() {
+            // being back in the same state means the walk repeats forever
+            if step % n == 0 {
+                if seen[curr as usize] {
+                    let (node, entry, length) = self.find_cycle(start, direction)?;
+                    return Err(PathError::Unreachable {
+                        end: self.name(end), node: self.name(node), entry, length,
+                    });
+                }
+                seen[curr as usize] = true;
+            }
+            curr = self.next(curr, turn)?;
+            path.push(curr);
+            if curr == end { break }
+        }
+
+        Ok(path)
+    }
+}
+
+/// Renders the steps of a walk, every `every` steps plus the last one,
+/// either as aligned text or as JSON lines.
+pub fn format_path(graph: &Graph, start: NodeId, path: &[NodeId], direction: &Direction,
+               every: usize, jsonl: bool) -> String {
+    let n = direction.turns.len();
+    let width = path.len().to_string().len();
+    let mut out = String::new();
+
+    for step in (0..=path.len()).filter(|s| s % every == 0 || *s == path.len()) {
+        let (node, turn) = match step {
+            0 => (start, None),
+            _ => (path[step - 1], Some(match direction.turns[(step - 1) % n] {
+                Turn::Left => 'L',
+                Turn::Right => 'R',
+            })),
+        };
+        out.push_str(&match (jsonl, turn) {
+            (true, Some(t)) => format!("{{\"step\":{},\"turn\":\"{}\",\"node\":\"{}\"}}\n",
+                                       step, t, graph.name(node)),
+            (true, None) => format!("{{\"step\":{},\"turn\":null,\"node\":\"{}\"}}\n",
+                                    step, graph.name(node)),
+            (false, _) => format!("{:>w$} {} {}\n", step, turn.unwrap_or('-'),
+                                  graph.name(node), w = width),
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ghost_steps(input: &str) -> (GhostMethod, Option<u64>) {
+        let lines: Vec<&str> = input.lines().filter(|l| !l.is_empty()).collect();
+        let direction = Direction::new(lines[0].chars().collect()).unwrap();
+        let graph = Graph::new(&lines[1..]);
+        let walk = graph.ghost_walk(&direction).unwrap();
+        (walk.method, walk.steps)
+    }
+
+    #[test]
+    fn example_ghost_walk() {
+        let input = "LR\n\n\
+            11A = (11B, XXX)\n11B = (XXX, 11Z)\n11Z = (11B, XXX)\n\
+            22A = (22B, XXX)\n22B = (22C, 22C)\n22C = (22Z, 22Z)\n\
+            22Z = (22B, 22B)\nXXX = (XXX, XXX)\n";
+        // 22A hits end nodes twice per cycle, so LCM alone cannot be trusted
+        assert_eq!(ghost_steps(input), (GhostMethod::Crt, Some(6)));
+    }
+
+    #[test]
+    fn irregular_cycles_use_crt() {
+        // 11A reaches an end node at steps 3, 5, 7, ... while 22A only at
+        // steps 2, 5, 8, ...: both are there at step 5 (mod 6)
+        let input = "L\n\
+            11A = (11B, 11B)\n11B = (11C, 11C)\n11C = (11Z, 11Z)\n11Z = (11C, 11C)\n\
+            22A = (22B, 22B)\n22B = (22Z, 22Z)\n22Z = (22C, 22C)\n22C = (22D, 22D)\n\
+            22D = (22Z, 22Z)\n";
+        assert_eq!(ghost_steps(input), (GhostMethod::Crt, Some(5)));
+    }
+
+    #[test]
+    fn shared_residues_are_merged_once() {
+        // eight ghosts on one ring, each on an end node every other step:
+        // 8^8 residue combinations, but only 8 distinct solutions
+        let name = |i: usize| format!("1{}{}", (b'A' + i as u8) as char, if i.is_multiple_of(2) { 'A' } else { 'Z' });
+        let mut input = "L\n".to_owned();
+        for i in 0..16 {
+            input.push_str(&format!("{} = ({}, {})\n", name(i), name((i + 1) % 16), name((i + 1) % 16)));
+        }
+        assert_eq!(ghost_steps(&input), (GhostMethod::Crt, Some(1)));
+    }
+
+    #[test]
+    fn simulation_gives_up() {
+        let input = "LR\n11A = (11B, XXX)\n11B = (XXX, 11Z)\n11Z = (11B, XXX)\n\
+            22A = (22B, XXX)\n22B = (22C, 22C)\n22C = (22Z, 22Z)\n\
+            22Z = (22B, 22B)\nXXX = (XXX, XXX)\n";
+        let lines: Vec<&str> = input.lines().collect();
+        let direction = Direction::new(lines[0].chars().collect()).unwrap();
+        let graph = Graph::new(&lines[1..]);
+        let ends: Vec<bool> = graph.names.iter().map(|n| n.ends_with('Z')).collect();
+        let starts = graph.starts('A');
+        let cycles: Vec<Cycle> = starts.iter().map(|s| graph.cycle(*s, &direction, &ends).unwrap()).collect();
+        let simulate = |budget| graph.ghost_simulation(&direction, &starts, &cycles, &ends, budget);
+        assert_eq!(simulate(10), Ok(Some(6)));
+        assert_eq!(simulate(5), Err(PathError::GaveUp { steps: 5 }));
+    }
+
+    #[test]
+    fn unreachable_ghosts() {
+        let input = "L\n11A = (11Z, 11Z)\n11Z = (11B, 11B)\n11B = (11B, 11B)\n\
+            22A = (22B, 22B)\n22B = (22Z, 22Z)\n22Z = (22Z, 22Z)\n";
+        assert_eq!(ghost_steps(input), (GhostMethod::Crt, None));
+    }
+
+    #[test]
+    fn invalid_instructions() {
+        assert_eq!(Direction::new(vec![]).unwrap_err(), "No instructions");
+        assert!(Direction::new("LRX".chars().collect()).is_err());
+        assert_eq!(Direction::new("LR".chars().collect()).unwrap().turns, [&Turn::Left, &Turn::Right]);
+    }
+
+    #[test]
+    fn example_path() {
+        let input = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+        let lines: Vec<&str> = input.lines().filter(|l| !l.is_empty()).collect();
+        let direction = Direction::new(lines[0].chars().collect()).unwrap();
+        let graph = Graph::new(&lines[1..]);
+        let (start, end) = (graph.id("AAA").unwrap(), graph.id("ZZZ").unwrap());
+        assert_eq!(graph.path(start, end, &direction).map(|p| p.len()), Ok(6));
+    }
+
+    #[test]
+    fn unreachable_path() {
+        let input = "LR\nAAA = (BBB, CCC)\nBBB = (CCC, CCC)\nCCC = (BBB, BBB)\nZZZ = (ZZZ, ZZZ)\n";
+        let lines: Vec<&str> = input.lines().collect();
+        let direction = Direction::new(lines[0].chars().collect()).unwrap();
+        let graph = Graph::new(&lines[1..]);
+        let (start, end) = (graph.id("AAA").unwrap(), graph.id("ZZZ").unwrap());
+        assert_eq!(graph.path(start, end, &direction), Err(PathError::Unreachable {
+            end: "ZZZ", node: "BBB", entry: 1, length: 2,
+        }));
+    }
+
+    #[test]
+    fn undefined_node_on_path() {
+        let input = "L\n\nAAA = (BBB, CCC)\nBBB = (DDD, ZZZ)\nCCC = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+        let lines: Vec<&str> = input.lines().filter(|l| !l.is_empty()).collect();
+        let direction = Direction::new(lines[0].chars().collect()).unwrap();
+        let graph = Graph::new(&lines[1..]);
+        let (start, end) = (graph.id("AAA").unwrap(), graph.id("ZZZ").unwrap());
+
+        assert_eq!(graph.path(start, end, &direction), Err(PathError::Undefined("DDD")));
+        assert_eq!(graph.find_cycle(start, &direction), Err(PathError::Undefined("DDD")));
+        assert_eq!(graph.ghost_walk(&direction).map(|w| w.steps), Err(PathError::Undefined("DDD")));
+
+        let ends: Vec<bool> = (0..graph.names.len() as NodeId).map(|id| id == end).collect();
+        let dot = graph.to_dot(&direction, &[(start, usize::MAX)], &ends);
+        assert!(dot.contains("\"AAA\" -> \"BBB\" [label=L, color=\"red\", penwidth=2];"));
+        assert!(dot.contains("\"BBB\" -> \"DDD\" [label=L, color=\"red\", penwidth=2];"));
+    }
+
+    #[test]
+    fn dot_highlights_path() {
+        let input = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+        let lines: Vec<&str> = input.lines().filter(|l| !l.is_empty()).collect();
+        let direction = Direction::new(lines[0].chars().collect()).unwrap();
+        let graph = Graph::new(&lines[1..]);
+        let (start, end) = (graph.id("AAA").unwrap(), graph.id("ZZZ").unwrap());
+        let ends: Vec<bool> = (0..3).map(|id| id == end).collect();
+        let dot = graph.to_dot(&direction, &[(start, 6)], &ends);
+
+        assert!(dot.contains("\"AAA\" [shape=box];"));
+        assert!(dot.contains("\"ZZZ\" [shape=doublecircle];"));
+        assert!(dot.contains("\"BBB\" -> \"ZZZ\" [label=R, color=\"red\", penwidth=2];"));
+        assert!(dot.contains("\"ZZZ\" -> \"ZZZ\" [label=L];"));
+    }
+
+    #[test]
+    fn static_analysis() {
+        let input = "LR\n\
+            AAA = (BBB, CCC)\nBBB = (DDD, AAA)\nCCC = (CCC, CCC)\nDDD = (EEE, AAA)\n";
+        let lines: Vec<&str> = input.lines().collect();
+        let direction = Direction::new(lines[0].chars().collect()).unwrap();
+        let graph = Graph::new(&lines[1..]);
+        let id = |name: &str| graph.id(name).unwrap();
+        let names = |visited: Vec<bool>| (0..graph.names.len() as NodeId)
+            .filter(|id| visited[*id as usize])
+            .map(|id| graph.name(id))
+            .collect::<Vec<&str>>();
+
+        assert_eq!(graph.undefined(), vec![id("EEE")]);
+        assert_eq!(graph.self_loops(), vec![id("CCC")]);
+        assert_eq!(names(graph.reachable(id("AAA"))), ["AAA", "BBB", "CCC", "DDD", "EEE"]);
+        assert_eq!(names(graph.forced_reachable(id("AAA"), &direction)), ["AAA", "BBB"]);
+        assert_eq!(names(graph.forced_reachable(id("DDD"), &direction)), ["DDD", "EEE"]);
+    }
+
+    #[test]
+    fn shortest_route() {
+        let input = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)\nYYY = (ZZZ, ZZZ)\n";
+        let lines: Vec<&str> = input.lines().filter(|l| !l.is_empty()).collect();
+        let graph = Graph::new(&lines[1..]);
+        let id = |name: &str| graph.id(name).unwrap();
+
+        assert_eq!(graph.shortest_route(id("AAA"), id("ZZZ")), Some((2, "LR".to_owned())));
+        assert_eq!(graph.shortest_route(id("AAA"), id("AAA")), Some((0, "".to_owned())));
+        assert_eq!(graph.shortest_route(id("AAA"), id("YYY")), None);
+    }
+
+    #[test]
+    fn path_output() {
+        let input = "LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+        let lines: Vec<&str> = input.lines().filter(|l| !l.is_empty()).collect();
+        let direction = Direction::new(lines[0].chars().collect()).unwrap();
+        let graph = Graph::new(&lines[1..]);
+        let (start, end) = (graph.id("AAA").unwrap(), graph.id("ZZZ").unwrap());
+        let path = graph.path(start, end, &direction).unwrap();
+
+        assert_eq!(format_path(&graph, start, &path, &direction, 4, false),
+                   "0 - AAA\n4 L AAA\n6 R ZZZ\n");
+        assert_eq!(format_path(&graph, start, &path, &direction, 5, true),
+                   "{\"step\":0,\"turn\":null,\"node\":\"AAA\"}\n\
+                    {\"step\":5,\"turn\":\"L\",\"node\":\"BBB\"}\n\
+                    {\"step\":6,\"turn\":\"R\",\"node\":\"ZZZ\"}\n");
+    }
+
+    #[test]
+    fn crt_merges_compatible_congruences() {
+        assert_eq!(crt(1, 2, 2, 3), Ok(Some((5, 6))));
+        assert_eq!(crt(2, 4, 4, 6), Ok(Some((10, 12))));
+        assert_eq!(crt(1, 4, 2, 6), Ok(None));
+    }
+
+    #[test]
+    fn overflows_are_errors() {
+        assert_eq!(lcm(6, 4), Some(12));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(crt(0, 1 << 40, 1, (1 << 40) + 1), Err(PathError::Overflow));
+        assert_eq!(crt(0, 1 << 40, 0, 1 << 41), Ok(Some((0, 1 << 41))));
+    }
+}
@@ -1,80 +1,74 @@
-use regex::Regex;
-use std::collections::HashMap;
+use common::{diag, diagln, Format, Report, Timer};
+use day_08::{format_path, Direction, Graph, NodeId};
 use std::env;
+use std::fs;
 use std::fs::File;
 use std::io::prelude::*;
+use std::iter::zip;
 
-#[derive(Debug, Hash, Eq, PartialEq)]
-enum Turn {
-    Left,
-    Right,
-}
-
-#[derive(Debug)]
-struct Direction<'a> {
-    turns: Vec<&'a Turn>,
-}
-
-impl Direction<'_> {
-    fn new(chars: Vec<char>) -> Self {
-        let turns = chars.iter().map(|c| match c {
-            'L' => &Turn::Left,
-            'R' => &Turn::Right,
-            _ => None.expect(&format!("Failed to parse turn from char {}", c))
-        }).collect();
-
-        Self { turns }
-    }
-}
-
-#[derive(Debug)]
-struct Graph<'a> {
-    nodes: HashMap<(&'a str, &'a Turn), &'a str>,
-}
-
-impl<'a> Graph<'a> {
-    fn new(lines: &'a [&str]) -> Graph<'a> {
-        let re = Regex::new(r"^(?<src>\w*) = \((?<left>\w*), (?<right>\w*)\)$").unwrap();
-
-        let mut nodes = HashMap::<(&str, &'a Turn), &str>::new();
-        for line in lines {
-            if let Some(c) = re.captures(line) {
-                let src = c.name("src").unwrap().as_str();
-                let left = c.name("left").unwrap().as_str();
-                let right = c.name("right").unwrap().as_str();
-                nodes.insert((src, &Turn::Left), left);
-                nodes.insert((src, &Turn::Right), right);
-            }
-        }
-
-        Graph { nodes }
-    }
-
-    fn path(&self, start: &'a str, end: &'a str, direction: &Direction) -> Vec<&'a str> {
-        let mut curr = start;
-        let mut path = vec![];
+fn main() {
+    let args: Vec<String> = env::args().collect();
 
-        for turn in direction.turns.iter().cycle() {
-            curr = self.nodes
-                .get(&(curr, *turn))
-                .expect(&format!("No transition from <{:?}, {:?}>", curr, turn));
-            path.push(curr);
-            if curr == end { break }
+    let usage = format!(
+        "Usage: {} [--cycles] [--analyze] [--shortest] [--print-path] [--path-jsonl <file>] \
+         [--every <n>] [--dot <file> [--dot-ghosts]] [--format <text|json>] [--time] <input>",
+        args[0]);
+    let mut print_path = false;
+    let mut path_jsonl: Option<&String> = None;
+    let mut every: usize = 1;
+    let mut report_cycles = false;
+    let mut shortest = false;
+    let mut analyze = false;
+    let mut dot: Option<&String> = None;
+    // export the ghost walks rather than the path from AAA
+    let mut dot_ghosts = false;
+    let mut format = Format::Text;
+    let mut time = false;
+    let mut input: Option<&String> = None;
+
+    let mut it = args.iter().skip(1);
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--cycles" => report_cycles = true,
+            "--analyze" => analyze = true,
+            "--shortest" => shortest = true,
+            "--print-path" => print_path = true,
+            "--path-jsonl" => match it.next() {
+                Some(v) => path_jsonl = Some(v),
+                None => { eprintln!("{}", usage); return },
+            },
+            "--every" => match it.next().and_then(|v| v.parse().ok()) {
+                Some(v) if v > 0 => every = v,
+                _ => { eprintln!("{}", usage); return },
+            },
+            "--dot" => match it.next() {
+                Some(v) => dot = Some(v),
+                None => { eprintln!("{}", usage); return },
+            },
+            "--dot-ghosts" => dot_ghosts = true,
+            "--format" => match it.next().and_then(|v| Format::parse(v)) {
+                Some(f) => format = f,
+                None => { eprintln!("{}", usage); return },
+            },
+            "--time" => time = true,
+            _ if input.is_none() => input = Some(arg),
+            _ => { eprintln!("{}", usage); return },
         }
-
-        path
     }
-}
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() != 2 {
-        eprintln!("Usage: {} <input>", args[0]);
+    let input = match input {
+        Some(p) => p,
+        None => { eprintln!("{}", usage); return },
+    };
+    // picks what --dot exports, so means nothing without it
+    if dot_ghosts && dot.is_none() {
+        eprintln!("{}", usage);
         return
     }
 
-    let mut file = match File::open(&args[1]) {
+    let mut timer = Timer::start();
+
+    let mut file = match File::open(input) {
         Ok(r) => r,
         Err(e) => { eprintln!("{}", e); return },
     };
@@ -85,15 +79,148 @@ fn main() {
         Err(e) => { eprintln!("{}", e); return },
     };
 
-    let lines: Vec<&str> = contents
-        .split('\n')
-        .filter(|l| l.len() > 0)
-        .collect();
+    // the instructions, then the network after a blank line
+    let mut lines = contents.split('\n');
+    let instructions = lines.next().unwrap_or("");
+    let lines: Vec<&str> = lines.filter(|l| !l.is_empty()).collect();
+
+    timer.lap("read");
+
+    let direction = match Direction::new(instructions.chars().collect()) {
+        Ok(d) => d,
+        Err(e) => { eprintln!("{}", e); return },
+    };
+    let graph = Graph::new(&lines);
+    timer.lap("parse");
+
+    let names = |ids: Vec<NodeId>| match ids.is_empty() {
+        true => "none".to_owned(),
+        false => ids.iter().map(|id| graph.name(*id)).collect::<Vec<&str>>().join(", "),
+    };
 
-    let direction = Direction::new(lines[0].chars().collect());
-    let graph = Graph::new(&lines[1..]);
+    // walks reaching these stop there, so warn upfront
+    let undefined = graph.undefined();
+    if !undefined.is_empty() {
+        eprintln!("undefined nodes: {}", names(undefined));
+    }
 
-    let path = graph.path("AAA", "ZZZ", &direction);
-    println!("steps: {:?}", path.len());
+    if analyze {
+        let total = graph.names.len();
+        let ids = |visited: Vec<bool>| (0..total as NodeId)
+            .filter(|id| visited[*id as usize]).collect::<Vec<NodeId>>();
+        diagln!(format, "nodes: {}", total);
+        let self_loops = graph.self_loops();
+        diagln!(format, "self-loops: {}", names(self_loops.clone()));
+
+        let mut starts = graph.starts('A');
+        if let Some(start) = graph.id("AAA").filter(|s| !starts.contains(s)) {
+            starts.insert(0, start);
+        }
+        for start in starts {
+            let reachable = ids(graph.reachable(start));
+            let forced = ids(graph.forced_reachable(start, &direction));
+            diagln!(format, "from {}: {} reachable, {} under instructions",
+                    graph.name(start), reachable.len(), forced.len());
+            let dead: Vec<NodeId> = forced.into_iter()
+                .filter(|id| !graph.is_defined(*id) || self_loops.contains(id))
+                .collect();
+            if !dead.is_empty() {
+                diagln!(format, "  trapped by: {}", names(dead));
+            }
+        }
+        timer.lap("analyze");
+    }
+
+    let mut part1 = None;
+    if let (Some(start), Some(end)) = (graph.id("AAA"), graph.id("ZZZ")) {
+        // steps to highlight: the whole path, or up to the end of the cycle
+        let path = graph.path(start, end, &direction);
+        timer.lap("path");
+        let steps = match path {
+            Ok(path) => {
+                if print_path {
+                    diag!(format, "{}", format_path(&graph, start, &path, &direction, every, false));
+                }
+                if let Some(out) = path_jsonl {
+                    let jsonl = format_path(&graph, start, &path, &direction, every, true);
+                    if let Err(e) = fs::write(out, jsonl) { eprintln!("{}", e); return }
+                }
+                if format == Format::Text {
+                    println!("steps: {:?}", path.len());
+                }
+                part1 = Some(path.len());
+                path.len()
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                match graph.find_cycle(start, &direction) {
+                    Ok((_, entry, length)) => entry + length,
+                    // the walk ends at an undefined node, highlight up to there
+                    Err(_) => usize::MAX,
+                }
+            },
+        };
+
+        if shortest {
+            match graph.shortest_route(start, end) {
+                Some((steps, turns)) => diagln!(format, "shortest route: {} ({})", steps, turns),
+                None => diagln!(format, "shortest route: none, ZZZ is unreachable"),
+            }
+        }
 
+        if let (Some(out), false) = (dot, dot_ghosts) {
+            let ends: Vec<bool> = (0..graph.names.len()).map(|id| id == end as usize).collect();
+            let dot = graph.to_dot(&direction, &[(start, steps)], &ends);
+            if let Err(e) = fs::write(out, dot) { eprintln!("{}", e); return }
+        }
+    } else if let (Some(_), false) = (dot, dot_ghosts) {
+        eprintln!("cannot export the path: AAA or ZZZ is missing");
+    }
+
+    let ghosts = graph.ghost_walk(&direction);
+    timer.lap("ghosts");
+    let ghost_steps = match &ghosts {
+        Ok(ghosts) => ghosts.steps,
+        Err(e) => { eprintln!("{}", e); None },
+    };
+    if let (true, Ok(ghosts)) = (report_cycles, &ghosts) {
+        for (start, c) in zip(&ghosts.starts, &ghosts.cycles) {
+            diagln!(format, "{}: entry {}, length {}, hits {:?}, {}", graph.name(*start), c.entry, c.length,
+                    c.hits, if c.is_simple() { "simple" } else { "irregular" });
+        }
+        diagln!(format, "method: {:?}", ghosts.method);
+    }
+    match (format, ghost_steps) {
+        (Format::Text, Some(steps)) => println!("ghost steps: {}", steps),
+        (Format::Text, None) if ghosts.is_ok() =>
+            println!("ghost steps: none, end nodes never reached together"),
+        (Format::Text, None) => (),
+        (Format::Json, _) => {
+            let mut report = Report::new(8, timer.elapsed());
+            if let Some(steps) = part1 { report = report.part1(steps) }
+            if let Some(steps) = ghost_steps { report = report.part2(steps) }
+            println!("{}", report);
+        },
+    }
+
+    if let (Some(out), true) = (dot, dot_ghosts) {
+        match &ghosts {
+            Ok(ghosts) if ghosts.starts.is_empty() =>
+                eprintln!("cannot export the ghost walks: no node ends in A"),
+            Ok(ghosts) => {
+                // every edge a ghost will ever take is taken by the end of its cycle
+                let walks: Vec<(NodeId, usize)> = zip(&ghosts.starts, &ghosts.cycles)
+                    .map(|(s, c)| (*s, c.entry + c.length))
+                    .collect();
+                let ends: Vec<bool> = graph.names.iter().map(|n| n.ends_with('Z')).collect();
+                let dot = graph.to_dot(&direction, &walks, &ends);
+                if let Err(e) = fs::write(out, dot) { eprintln!("{}", e); }
+            },
+            Err(e) => eprintln!("cannot export the ghost walks: {}", e),
+        }
+    }
+
+    if time {
+        eprint!("{}", timer);
+    }
 }
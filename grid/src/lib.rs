@@ -0,0 +1,275 @@
+//! Dense 2-D grid shared by the puzzles laid out on a character block.
+//!
+//! Cells are addressed by `(x, y)`, column first, with `(0, 0)` at the
+//! top left corner.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, PartialEq)]
+pub enum GridError {
+    /// A row's width differs from the first row's one.
+    RaggedRow { y: usize, width: usize, expected: usize },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridError::RaggedRow { y, width, expected } =>
+                write!(f, "row {} is {} cells wide, expected {}", y, width, expected),
+        }
+    }
+}
+
+/// Half-open rectangle of cells, from `(x0, y0)` included to `(x1, y1)`
+/// excluded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub x0: usize, pub y0: usize,
+    pub x1: usize, pub y1: usize,
+}
+
+impl Rect {
+    /// Cells of the rectangle, row by row.
+    pub fn coords(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (x0, x1) = (self.x0, self.x1);
+        (self.y0..self.y1).flat_map(move |y| (x0..x1).map(move |x| (x, y)))
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        self.x0 <= x && x < self.x1 && self.y0 <= y && y < self.y1
+    }
+
+    /// Grows the rectangle by `n` cells on every side, without leaving
+    /// a `width` by `height` grid.
+    pub fn expand(&self, n: usize, width: usize, height: usize) -> Rect {
+        Rect {
+            x0: self.x0.saturating_sub(n),
+            y0: self.y0.saturating_sub(n),
+            x1: usize::min(self.x1 + n, width),
+            y1: usize::min(self.y1 + n, height),
+        }
+    }
+
+    /// Smallest rectangle containing both.
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            x0: usize::min(self.x0, other.x0),
+            y0: usize::min(self.y0, other.y0),
+            x1: usize::max(self.x1, other.x1),
+            y1: usize::max(self.y1, other.y1),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self where T: Clone {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let width = rows.first().map_or(0, |r| r.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(GridError::RaggedRow { y, width: row.len(), expected: width });
+            }
+            cells.extend(row);
+        }
+        Ok(Grid { width, height, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect { x0: 0, y0: 0, x1: self.width, y1: self.height }
+    }
+
+    fn offset(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.offset(x, y).map(|o| &self.cells[o])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.offset(x, y).map(|o| &mut self.cells[o])
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [T]> {
+        (y < self.height).then(|| &mut self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks of an empty slice would panic on a zero width
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let n = if x < self.width { self.height } else { 0 };
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(n)
+    }
+
+    /// Cells of `rect` within the grid, with their coordinates.
+    pub fn view(&self, rect: Rect) -> impl Iterator<Item = (usize, usize, &T)> {
+        let rect = Rect {
+            x1: usize::min(rect.x1, self.width),
+            y1: usize::min(rect.y1, self.height),
+            ..rect
+        };
+        rect.coords().map(move |(x, y)| (x, y, &self[(x, y)]))
+    }
+
+    /// Every cell, row by row, with its coordinates.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &T)> {
+        self.view(self.bounds())
+    }
+
+    /// Orthogonal neighbours within the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbours(x, y, &[(0, -1), (-1, 0), (1, 0), (0, 1)])
+    }
+
+    /// Orthogonal and diagonal neighbours within the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbours(x, y, &[
+            (-1, -1), (0, -1), (1, -1),
+            (-1, 0), (1, 0),
+            (-1, 1), (0, 1), (1, 1),
+        ])
+    }
+
+    fn neighbours(&self, x: usize, y: usize, deltas: &'static [(isize, isize)])
+            -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        deltas.iter().filter_map(move |(dx, dy)| {
+            let nx = x.checked_add_signed(*dx)?;
+            let ny = y.checked_add_signed(*dy)?;
+            (nx < width && ny < height).then_some((nx, ny))
+        })
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+}
+
+impl Grid<char> {
+    /// Parses a block of lines, one row each, ignoring empty lines and
+    /// padding rows shorter than the longest one with `pad`.
+    pub fn parse(block: &str, pad: char) -> Self {
+        let rows: Vec<Vec<char>> = block.lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| l.chars().collect())
+            .collect();
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let rows = rows.into_iter()
+            .map(|mut r| { r.resize(width, pad); r })
+            .collect();
+        Grid::from_rows(rows).unwrap()
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        match self.offset(x, y) {
+            Some(o) => &self.cells[o],
+            None => panic!("({}, {}) is out of the {}x{} grid", x, y, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        match self.offset(x, y) {
+            Some(o) => &mut self.cells[o],
+            None => panic!("({}, {}) is out of the {}x{} grid", x, y, self.width, self.height),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows().take(self.height) {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        let grid = Grid::parse("ab\nc\n\n", '.');
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid.to_string(), "ab\nc.\n");
+    }
+
+    #[test]
+    fn bounds_checked_access() {
+        let mut grid = Grid::new(3, 2, 0);
+        *grid.get_mut(2, 1).unwrap() = 5;
+        assert_eq!(grid.get(2, 1), Some(&5));
+        assert_eq!(grid.get(3, 1), None);
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(grid[(2, 1)], 5);
+    }
+
+    #[test]
+    fn ragged_rows() {
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]),
+                   Err(GridError::RaggedRow { y: 1, width: 1, expected: 2 }));
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = Grid::new(3, 3, ());
+        assert_eq!(grid.neighbours4(0, 0).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours8(1, 1).count(), 8);
+        assert_eq!(grid.neighbours8(2, 2).collect::<Vec<_>>(), [(1, 1), (2, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn views() {
+        let grid = Grid::parse("abc\ndef\nghi\n", '.');
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.row(3), None);
+        assert_eq!(grid.column(2).collect::<String>(), "cfi");
+        assert_eq!(grid.column(3).count(), 0);
+
+        let rect = Rect { x0: 1, y0: 1, x1: 4, y1: 4 };
+        let cells: String = grid.view(rect).map(|(_, _, c)| c).collect();
+        assert_eq!(cells, "efhi");
+
+        let around = Rect { x0: 0, y0: 0, x1: 1, y1: 1 }.expand(1, 3, 3);
+        assert_eq!(around, Rect { x0: 0, y0: 0, x1: 2, y1: 2 });
+        assert!(around.contains(1, 1) && !around.contains(2, 1));
+    }
+}